@@ -1,8 +1,9 @@
-use crate::client::{deserialize_response, get_random_u32};
 use crate::{DelugeClient, Response};
 use rogue_logging::Error;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::Value;
+use std::path::Path;
+use tokio::fs::read;
 
 impl DelugeClient {
     /// Add torrents by file
@@ -11,20 +12,69 @@ impl DelugeClient {
     /// Deluge will throw an exception and the API call will hang indefinitely
     /// if the torrent hash is already in the session.
     ///
+    /// If [`DelugeClient::free_space_guard`] is set the torrents are not added
+    /// unless [`DelugeClient::check_free_space`] succeeds.
+    ///
     /// # See Also
     /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
     pub async fn add_torrents(
         &mut self,
         torrents: Vec<TorrentPath>,
     ) -> Result<Response<Vec<Torrent>>, Error> {
-        let method = "web.add_torrents";
-        let data = json!({
-            "method": method,
-            "params": [ torrents ],
-            "id": get_random_u32()
-        });
-        let response = self.request(method, data).await?;
-        deserialize_response(method, response).await
+        if self.free_space_guard {
+            self.check_free_space(&torrents).await?;
+        }
+        self.call("web.add_torrents", (torrents,)).await
+    }
+
+    /// Add a torrent by magnet URI and get its id
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn add_torrent_magnet(
+        &mut self,
+        uri: &str,
+        options: Options,
+    ) -> Result<Response<String>, Error> {
+        self.call("core.add_torrent_magnet", (uri, options)).await
+    }
+
+    /// Download a torrent file to the web server and get its path
+    ///
+    /// Add the downloaded file with [`DelugeClient::add_torrents`].
+    ///
+    /// Only available with the web transport as the daemon has no equivalent.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn download_torrent_from_url(
+        &mut self,
+        url: &str,
+    ) -> Result<Response<String>, Error> {
+        self.call("web.download_torrent_from_url", (url,)).await
+    }
+
+    /// Upload a torrent file to the web server and get its path
+    ///
+    /// Add the uploaded file with [`DelugeClient::add_torrents`].
+    ///
+    /// With the daemon transport the file is kept in memory by the client.
+    pub async fn upload_torrent(&mut self, path: &Path) -> Result<String, Error> {
+        let file_name = path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .ok_or_else(|| Error {
+                action: "get file name of torrent".to_owned(),
+                message: format!("Path has no file name: {}", path.display()),
+                ..Error::default()
+            })?;
+        let bytes = read(path).await.map_err(|e| Error {
+            action: "read torrent file".to_owned(),
+            domain: Some("file system".to_owned()),
+            message: e.to_string(),
+            ..Error::default()
+        })?;
+        self.transport.upload(file_name, bytes).await
     }
 }
 
@@ -98,19 +148,15 @@ impl<'de> Deserialize<'de> for Torrent {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{DelugeClient, DelugeClientOptions};
+    use crate::testing::MockServer;
     use log::trace;
-    use rogue_config::OptionsProvider;
-    use rogue_config::YamlOptionsProvider;
-    use rogue_logging::{Error, LoggerBuilder};
+    use rogue_logging::Error;
 
     #[tokio::test]
-    #[ignore]
     async fn add_torrents() -> Result<(), Error> {
         // Arrange
-        let _ = LoggerBuilder::new().create();
-        let options: DelugeClientOptions = YamlOptionsProvider::get()?;
-        let mut client = DelugeClient::from_options(options.clone());
+        let server = MockServer::start().await?;
+        let mut client = server.client();
         let torrent = TorrentPath {
             path: "/srv/shared/tests/example-1.torrent".to_owned(),
             options: Options {
@@ -127,7 +173,9 @@ mod tests {
         trace!("{}", response.to_json_pretty());
 
         // Assert
-        let _result = response.get_result("add_torrents")?;
+        let result = response.get_result("add_torrents")?;
+        assert_eq!(result.len(), 1);
+        assert_eq!(server.state().torrents.len(), 1);
         Ok(())
     }
 }
@@ -0,0 +1,189 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+impl DelugeClient {
+    /// Get the watch folders keyed by id.
+    ///
+    /// Requires the `AutoAdd` plugin to be enabled.
+    pub async fn get_watchdirs(&mut self) -> Result<Response<HashMap<u32, WatchDir>>, Error> {
+        self.call("autoadd.get_watchdirs", ()).await
+    }
+
+    /// Add a watch folder and get its id.
+    ///
+    /// `path` is required and must exist on the daemon.
+    pub async fn add_watchdir(&mut self, options: WatchDir) -> Result<Response<u32>, Error> {
+        self.call("autoadd.add", (options,)).await
+    }
+
+    /// Remove a watch folder.
+    pub async fn remove_watchdir(&mut self, id: u32) -> Result<Response<()>, Error> {
+        self.call("autoadd.remove", (id,)).await
+    }
+
+    /// Set the options of a watch folder.
+    ///
+    /// Only the fields that are set are sent so partial options can be applied.
+    pub async fn set_watchdir_options(
+        &mut self,
+        id: u32,
+        options: WatchDir,
+    ) -> Result<Response<()>, Error> {
+        self.call("autoadd.set_options", (id, options)).await
+    }
+
+    /// Start watching a watch folder.
+    pub async fn enable_watchdir(&mut self, id: u32) -> Result<Response<()>, Error> {
+        self.call("autoadd.enable_watchdir", (id,)).await
+    }
+
+    /// Stop watching a watch folder.
+    pub async fn disable_watchdir(&mut self, id: u32) -> Result<Response<()>, Error> {
+        self.call("autoadd.disable_watchdir", (id,)).await
+    }
+}
+
+/// Options of an `AutoAdd` watch folder
+///
+/// Most options are only applied when their `_toggle` is `true`.
+///
+/// Keys that are not modelled are kept in `other`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct WatchDir {
+    /// Watch the folder for new `.torrent` files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Directory to watch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Absolute directory to watch, set by Deluge
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abspath: Option<String>,
+    /// User that owns the added torrents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+
+    /// Enable [`WatchDir::download_location`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_location_toggle: Option<bool>,
+    /// Directory to download the files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_location: Option<String>,
+    /// Enable [`WatchDir::add_paused`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add_paused_toggle: Option<bool>,
+    /// Start the torrent paused
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add_paused: Option<bool>,
+    /// Enable [`WatchDir::max_download_speed`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_download_speed_toggle: Option<bool>,
+    /// Maximum download speed in KiB/s, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_download_speed: Option<f64>,
+    /// Enable [`WatchDir::max_upload_speed`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_upload_speed_toggle: Option<bool>,
+    /// Maximum upload speed in KiB/s, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_upload_speed: Option<f64>,
+    /// Enable [`WatchDir::max_connections`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_connections_toggle: Option<bool>,
+    /// Maximum number of connections, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_connections: Option<i64>,
+    /// Enable [`WatchDir::max_upload_slots`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_upload_slots_toggle: Option<bool>,
+    /// Maximum number of upload slots, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_upload_slots: Option<i64>,
+    /// Enable [`WatchDir::move_completed`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_completed_toggle: Option<bool>,
+    /// Move completed downloads to another directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_completed: Option<bool>,
+    /// Enable [`WatchDir::move_completed_path`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_completed_path_toggle: Option<bool>,
+    /// Path to move completed downloads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_completed_path: Option<String>,
+    /// Enable [`WatchDir::seed_mode`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed_mode_toggle: Option<bool>,
+    /// Add the torrent in seed mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed_mode: Option<bool>,
+    /// Enable [`WatchDir::label`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_toggle: Option<bool>,
+    /// Label to apply to the torrent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Enable [`WatchDir::queue_to_top`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_to_top_toggle: Option<bool>,
+    /// Add the torrent to the top of the queue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_to_top: Option<bool>,
+
+    /// Enable [`WatchDir::append_extension`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub append_extension_toggle: Option<bool>,
+    /// Extension to append to `.torrent` files once added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub append_extension: Option<String>,
+    /// Enable [`WatchDir::copy_torrent`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copy_torrent_toggle: Option<bool>,
+    /// Directory to copy `.torrent` files to once added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copy_torrent: Option<String>,
+    /// Delete the copied `.torrent` file when the torrent is removed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete_copy_torrent_toggle: Option<bool>,
+
+    /// Options that are not modelled
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use log::trace;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn get_watchdirs() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+        let options = WatchDir {
+            path: Some("/srv/watch".to_owned()),
+            ..WatchDir::default()
+        };
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.add_watchdir(options).await?;
+        trace!("{}", response.to_json_pretty());
+        let id = response.get_result("add_watchdir")?;
+        let response = client.get_watchdirs().await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_watchdirs")?;
+        let watchdir = result.get(&id).expect("should have added watch folder");
+        assert_eq!(watchdir.abspath.as_deref(), Some("/srv/watch"));
+        Ok(())
+    }
+}
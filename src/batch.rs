@@ -0,0 +1,235 @@
+use crate::call::to_params;
+use crate::client::check_id;
+use crate::{DelugeClient, Response, RpcMethod, TransportRequest};
+use rogue_logging::Error;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::marker::PhantomData;
+
+impl DelugeClient {
+    /// Send every call in a batch.
+    ///
+    /// [`WebTransport`](crate::WebTransport) sends the calls concurrently,
+    /// other transports may send them one after another.
+    ///
+    /// The responses are returned in the order the calls were added and each is
+    /// correlated to its request by id. A failed call does not fail the batch, use
+    /// the [`BatchHandle`] from [`Batch::add`] to get the result of each call.
+    pub async fn call_batch(&mut self, batch: Batch) -> BatchResponses {
+        let requests: Vec<TransportRequest> = batch
+            .requests
+            .into_iter()
+            .map(|request| TransportRequest {
+                id: self.next_id(),
+                method: request.method,
+                params: request.params,
+            })
+            .collect();
+        let checks: Vec<(u32, String)> = requests
+            .iter()
+            .map(|request| (request.id, request.method.clone()))
+            .collect();
+        let responses = self.transport.send_batch(requests).await;
+        let responses = responses
+            .into_iter()
+            .zip(checks)
+            .map(|(response, (id, method))| {
+                let response = response?;
+                check_id(&method, id, &response)?;
+                Ok(response)
+            })
+            .collect();
+        BatchResponses { responses }
+    }
+}
+
+/// Calls to send together with [`DelugeClient::call_batch`]
+#[derive(Debug, Default)]
+pub struct Batch {
+    requests: Vec<BatchRequest>,
+}
+
+#[derive(Debug)]
+struct BatchRequest {
+    method: String,
+    params: Value,
+}
+
+impl Batch {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a call to the batch.
+    ///
+    /// `params` must serialize to a JSON array, typically a tuple such as `(id,)`,
+    /// or `()` for no parameters.
+    ///
+    /// Returns a handle to get the typed result from the [`BatchResponses`].
+    pub fn add<P: Serialize, R: DeserializeOwned>(
+        &mut self,
+        method: &str,
+        params: P,
+    ) -> Result<BatchHandle<R>, Error> {
+        self.requests.push(BatchRequest {
+            method: method.to_owned(),
+            params: to_params(method, params)?,
+        });
+        Ok(BatchHandle {
+            index: self.requests.len() - 1,
+            marker: PhantomData,
+        })
+    }
+
+    /// Add a call defined by an [`RpcMethod`] to the batch.
+    pub fn add_method<M: RpcMethod>(&mut self, method: M) -> Result<BatchHandle<M::Output>, Error> {
+        self.add(M::METHOD, method.params())
+    }
+
+    /// Number of calls in the batch
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+}
+
+/// Handle to the typed result of a call added to a [`Batch`]
+#[derive(Debug)]
+pub struct BatchHandle<R> {
+    index: usize,
+    marker: PhantomData<R>,
+}
+
+impl<R> BatchHandle<R> {
+    /// Position of the call in the batch
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// Responses of a [`Batch`] in the order the calls were added
+#[derive(Debug)]
+pub struct BatchResponses {
+    responses: Vec<Result<Response<Value>, Error>>,
+}
+
+impl BatchResponses {
+    /// Get the typed response of a call
+    ///
+    /// Returns an error if the call failed or the result could not be deserialized.
+    pub fn get<R: DeserializeOwned>(&self, handle: &BatchHandle<R>) -> Result<Response<R>, Error> {
+        let response = match self.responses.get(handle.index) {
+            Some(Ok(response)) => response,
+            Some(Err(error)) => return Err(error.clone()),
+            None => {
+                return Err(Error {
+                    action: "get batch response".to_owned(),
+                    message: format!("No response at index {}", handle.index),
+                    ..Error::default()
+                })
+            }
+        };
+        let result = match response.result.clone() {
+            Some(result) => Some(serde_json::from_value(result).map_err(|e| Error {
+                action: "deserialize batch response".to_owned(),
+                domain: Some("deserialization".to_owned()),
+                message: e.to_string(),
+                status_code: response.status_code,
+                ..Error::default()
+            })?),
+            None => None,
+        };
+        Ok(Response {
+            status_code: response.status_code,
+            result,
+            error: response.error.clone(),
+            id: response.id,
+        })
+    }
+
+    /// Number of responses
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// Get the untyped responses in the order the calls were added
+    #[must_use]
+    pub fn into_vec(self) -> Vec<Result<Response<Value>, Error>> {
+        self.responses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_hosts::Host;
+    use crate::testing::MockServer;
+    use crate::DelugeClientFactory;
+    use log::trace;
+    use rogue_logging::Error;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn call_batch() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+        let mut batch = Batch::new();
+        let hosts = batch.add::<_, Vec<Host>>("web.get_hosts", ())?;
+        let connected = batch.add::<_, bool>("web.connected", ())?;
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let responses = client.call_batch(batch).await;
+
+        // Assert
+        assert_eq!(responses.len(), 2);
+        let hosts = responses.get(&hosts)?.get_result("get_hosts")?;
+        assert!(!hosts.is_empty());
+        let _connected = responses.get(&connected)?.get_result("connected")?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn call_batch_rate_limited() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        server.state().latency = Duration::from_millis(200);
+        let mut options = server.options();
+        options.rate_limit_count = Some(2);
+        options.rate_limit_duration = Some(1);
+        let mut client = DelugeClientFactory { options }.create();
+        let mut batch = Batch::new();
+        for _ in 0..3 {
+            batch.add::<_, bool>("web.connected", ())?;
+        }
+        let start = Instant::now();
+
+        // Act
+        client.login().await?;
+        let responses = client.call_batch(batch).await;
+
+        // Assert
+        // The login and first call use the permits of the first second so the
+        // last two calls wait for the next
+        assert!(start.elapsed() >= Duration::from_millis(1200));
+        assert_eq!(responses.len(), 3);
+        assert_eq!(server.state().calls.len(), 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+//! Decode the bencode format of `.torrent` files
+//!
+//! Only decoding is supported as it is only used to read torrent files for the
+//! [`DaemonTransport`](crate::DaemonTransport).
+//!
+//! # See Also
+//! - <https://www.bittorrent.org/beps/bep_0003.html>
+use rogue_logging::Error;
+use std::collections::BTreeMap;
+
+/// A decoded bencode value borrowing its strings from the encoded bytes
+#[derive(Debug, PartialEq)]
+pub(crate) enum Bencode<'a> {
+    Int(i64),
+    Bytes(&'a [u8]),
+    List(Vec<Bencode<'a>>),
+    Dict(BTreeMap<&'a [u8], Bencode<'a>>),
+}
+
+impl<'a> Bencode<'a> {
+    /// Get the value of a key if this is a dictionary
+    pub(crate) fn get(&self, key: &str) -> Option<&Bencode<'a>> {
+        match self {
+            Bencode::Dict(map) => map.get(key.as_bytes()),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_int(&self) -> Option<i64> {
+        match self {
+            Bencode::Int(int) => Some(*int),
+            _ => None,
+        }
+    }
+
+    /// Get a byte string as UTF-8, replacing invalid sequences
+    pub(crate) fn as_string(&self) -> Option<String> {
+        match self {
+            Bencode::Bytes(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_list(&self) -> Option<&[Bencode<'a>]> {
+        match self {
+            Bencode::List(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+/// Decode a value
+pub(crate) fn decode(bytes: &[u8]) -> Result<Bencode<'_>, Error> {
+    let mut decoder = Decoder { bytes, position: 0 };
+    let value = decoder.value()?;
+    if decoder.position != bytes.len() {
+        return Err(decode_error(format!(
+            "{} unexpected bytes after value",
+            bytes.len() - decoder.position
+        )));
+    }
+    Ok(value)
+}
+
+/// Get the encoded bytes of the value of a key in a top level dictionary
+///
+/// The info hash of a torrent is the SHA-1 of the encoded `info` value.
+pub(crate) fn raw_value<'a>(bytes: &'a [u8], key: &str) -> Result<Option<&'a [u8]>, Error> {
+    let mut decoder = Decoder { bytes, position: 0 };
+    if decoder.byte()? != b'd' {
+        return Err(decode_error("Expected a dictionary".to_owned()));
+    }
+    while decoder.peek()? != b'e' {
+        let current = decoder.bytes_value()?;
+        let start = decoder.position;
+        decoder.value()?;
+        if current == key.as_bytes() {
+            return Ok(bytes.get(start..decoder.position));
+        }
+    }
+    Ok(None)
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn value(&mut self) -> Result<Bencode<'a>, Error> {
+        let value = match self.peek()? {
+            b'i' => {
+                self.position += 1;
+                let digits = self.until(b'e')?;
+                let int = String::from_utf8_lossy(digits)
+                    .parse()
+                    .map_err(|e| decode_error(format!("Invalid integer: {e}")))?;
+                Bencode::Int(int)
+            }
+            b'l' => {
+                self.position += 1;
+                let mut items = Vec::new();
+                while self.peek()? != b'e' {
+                    items.push(self.value()?);
+                }
+                self.position += 1;
+                Bencode::List(items)
+            }
+            b'd' => {
+                self.position += 1;
+                let mut map = BTreeMap::new();
+                while self.peek()? != b'e' {
+                    let key = self.bytes_value()?;
+                    map.insert(key, self.value()?);
+                }
+                self.position += 1;
+                Bencode::Dict(map)
+            }
+            b'0'..=b'9' => Bencode::Bytes(self.bytes_value()?),
+            byte => return Err(decode_error(format!("Unexpected type code {byte}"))),
+        };
+        Ok(value)
+    }
+
+    /// Decode a `length:bytes` string
+    fn bytes_value(&mut self) -> Result<&'a [u8], Error> {
+        let len = self.until(b':')?;
+        let len = String::from_utf8_lossy(len)
+            .parse::<usize>()
+            .map_err(|e| decode_error(format!("Invalid string length: {e}")))?;
+        self.take(len)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        let byte = self.peek()?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn peek(&self) -> Result<u8, Error> {
+        self.bytes
+            .get(self.position)
+            .copied()
+            .ok_or_else(|| decode_error("Unexpected end of data".to_owned()))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let end = self
+            .position
+            .checked_add(len)
+            .ok_or_else(|| decode_error("Unexpected end of data".to_owned()))?;
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .ok_or_else(|| decode_error("Unexpected end of data".to_owned()))?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn until(&mut self, terminator: u8) -> Result<&'a [u8], Error> {
+        let remaining = self.bytes.get(self.position..).unwrap_or_default();
+        let len = remaining
+            .iter()
+            .position(|x| *x == terminator)
+            .ok_or_else(|| decode_error("Unterminated value".to_owned()))?;
+        let bytes = self.take(len)?;
+        self.position += 1;
+        Ok(bytes)
+    }
+}
+
+fn decode_error(message: String) -> Error {
+    Error {
+        action: "decode bencode".to_owned(),
+        domain: Some("deserialization".to_owned()),
+        message,
+        ..Error::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_torrent() -> Result<(), Error> {
+        // Arrange
+        let bytes = b"d8:announce3:url4:infod6:lengthi5e4:name1:aee";
+
+        // Act
+        let torrent = decode(bytes)?;
+        let info = raw_value(bytes, "info")?;
+
+        // Assert
+        let name = torrent.get("info").and_then(|x| x.get("name"));
+        assert_eq!(name.and_then(Bencode::as_string).as_deref(), Some("a"));
+        let length = torrent.get("info").and_then(|x| x.get("length"));
+        assert_eq!(length.and_then(Bencode::as_int), Some(5));
+        assert_eq!(info, Some(&b"d6:lengthi5e4:name1:ae"[..]));
+        Ok(())
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert!(decode(b"d4:name").is_err());
+        assert!(decode(b"i1ei2e").is_err());
+        assert!(decode(b"99999999999999999999:a").is_err());
+        assert!(raw_value(b"l1:ae", "info").is_err());
+    }
+}
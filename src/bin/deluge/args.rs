@@ -0,0 +1,227 @@
+use clap::{Args, Parser, Subcommand};
+use deluge_api::add_torrents::Options;
+use deluge_api::get_torrents::FilterOptions;
+use rogue_logging::Verbosity;
+use std::collections::HashMap;
+
+/// Manage Deluge from the command line
+///
+/// Options are read from `config.yml` in the working directory, the same file
+/// as `DelugeClientOptions`.
+#[derive(Debug, Parser)]
+#[command(name = "deluge", version)]
+pub struct Cli {
+    /// Print JSON instead of a table
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Level of log messages to print
+    #[arg(long, global = true, value_enum, default_value_t = Verbosity::Warn)]
+    pub verbosity: Verbosity,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// List the daemons known to the web server
+    Hosts,
+    /// Connect the web server to a daemon
+    Connect {
+        /// Id of the host, defaults to the first host
+        host_id: Option<String>,
+    },
+    /// List torrents
+    List(ListArgs),
+    /// Show the status of a torrent
+    Status {
+        /// Id of the torrent
+        id: String,
+    },
+    /// Add a torrent from a file, magnet URI or URL
+    Add(AddArgs),
+    /// Remove torrents
+    Remove {
+        /// Ids of the torrents
+        #[arg(required = true)]
+        ids: Vec<String>,
+        /// Also delete the downloaded files
+        #[arg(long)]
+        remove_data: bool,
+    },
+    /// Pause torrents
+    Pause {
+        /// Ids of the torrents
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Resume torrents
+    Resume {
+        /// Ids of the torrents
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Move the files of torrents to another directory
+    Move {
+        /// Directory to move the files to
+        destination: String,
+        /// Ids of the torrents
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Set or remove the label of a torrent
+    Label {
+        /// Id of the torrent
+        id: String,
+        /// Label to set, removes the label if omitted
+        label: Option<String>,
+    },
+    /// Read or write the daemon configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Get configuration values, all of them if no keys are given
+    Get { keys: Vec<String> },
+    /// Set a configuration value
+    ///
+    /// The value is parsed as JSON and falls back to a string.
+    Set { key: String, value: String },
+}
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    /// Only torrents in a state, for example `Seeding`
+    #[arg(long, value_delimiter = ',')]
+    pub state: Vec<String>,
+    /// Only torrents with a label
+    #[arg(long, value_delimiter = ',')]
+    pub label: Vec<String>,
+    /// Only torrents of a tracker host
+    #[arg(long, value_delimiter = ',')]
+    pub tracker_host: Vec<String>,
+    /// Only torrents with a name
+    #[arg(long, value_delimiter = ',')]
+    pub name: Vec<String>,
+    /// Only torrents of an owner
+    #[arg(long, value_delimiter = ',')]
+    pub owner: Vec<String>,
+    /// Only torrents with an id
+    #[arg(long, value_delimiter = ',')]
+    pub id: Vec<String>,
+    /// Filter defined by a plugin as `key=value`
+    #[arg(long, value_parser = parse_filter)]
+    pub filter: Vec<(String, String)>,
+}
+
+impl ListArgs {
+    pub fn to_filters(&self) -> FilterOptions {
+        let mut other: HashMap<String, Vec<String>> = HashMap::new();
+        for (key, value) in &self.filter {
+            other.entry(key.clone()).or_default().push(value.clone());
+        }
+        FilterOptions {
+            state: non_empty(&self.state),
+            tracker_host: non_empty(&self.tracker_host),
+            label: non_empty(&self.label),
+            name: non_empty(&self.name),
+            owner: non_empty(&self.owner),
+            id: non_empty(&self.id),
+            other,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct AddArgs {
+    /// Path of a `.torrent` file, a magnet URI or an HTTP URL
+    pub source: String,
+    /// Directory to download the files
+    #[arg(long)]
+    pub download_location: Option<String>,
+    /// Start the torrent paused
+    #[arg(long)]
+    pub paused: bool,
+    /// Skip the hash check when adding the torrent
+    #[arg(long)]
+    pub skip_hash_check: bool,
+}
+
+impl AddArgs {
+    pub fn to_options(&self) -> Options {
+        Options {
+            download_location: self.download_location.clone(),
+            add_paused: self.paused.then_some(true),
+            skip_hash_check: self.skip_hash_check.then_some(true),
+            ..Options::default()
+        }
+    }
+}
+
+fn non_empty(values: &[String]) -> Option<Vec<String>> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.to_vec())
+    }
+}
+
+fn parse_filter(value: &str) -> Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .ok_or_else(|| format!("Expected key=value: {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_list_filters() {
+        // Arrange
+        let args = [
+            "deluge",
+            "list",
+            "--state",
+            "Seeding,Paused",
+            "--label",
+            "linux",
+            "--filter",
+            "custom=a",
+            "--json",
+        ];
+
+        // Act
+        let cli = Cli::try_parse_from(args).expect("args should parse");
+
+        // Assert
+        assert!(cli.json);
+        let filters = match cli.command {
+            Command::List(list) => Some(list.to_filters()),
+            _ => None,
+        };
+        let filters = filters.expect("should be list");
+        assert_eq!(
+            filters.state,
+            Some(vec!["Seeding".to_owned(), "Paused".to_owned()])
+        );
+        assert_eq!(filters.label, Some(vec!["linux".to_owned()]));
+        assert_eq!(filters.tracker_host, None);
+        assert_eq!(filters.other.get("custom"), Some(&vec!["a".to_owned()]));
+    }
+
+    #[test]
+    fn parse_requires_ids() {
+        // Act
+        let result = Cli::try_parse_from(["deluge", "pause"]);
+
+        // Assert
+        assert!(result.is_err());
+    }
+}
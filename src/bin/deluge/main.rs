@@ -0,0 +1,244 @@
+use crate::args::{AddArgs, Cli, Command, ConfigCommand};
+use crate::output::{print_fields, print_json, print_table};
+use clap::Parser;
+use deluge_api::add_torrents::TorrentPath;
+use deluge_api::core_config::CoreConfig;
+use deluge_api::{DelugeClient, DelugeClientFactory, DelugeClientOptions, Response};
+use rogue_config::{OptionsProvider, YamlOptionsProvider};
+use rogue_logging::{Error, LoggerBuilder};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::path::Path;
+use std::process::ExitCode;
+
+mod args;
+mod output;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let _ = LoggerBuilder::new().with_verbosity(cli.verbosity).create();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            error.log();
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Error> {
+    let options: DelugeClientOptions = YamlOptionsProvider::get()?;
+    let factory = DelugeClientFactory { options };
+    let mut client = factory.create();
+    if !client.login().await?.get_result("login")? {
+        return Err(Error {
+            action: "login".to_owned(),
+            domain: Some("Deluge API".to_owned()),
+            message: "Password was rejected".to_owned(),
+            ..Error::default()
+        });
+    }
+    match cli.command {
+        Command::Hosts => hosts(&mut client, cli.json).await,
+        Command::Connect { host_id } => connect(&mut client, host_id).await,
+        Command::List(args) => {
+            let torrents = client
+                .get_torrents(args.to_filters())
+                .await?
+                .get_result("get torrents")?;
+            if cli.json {
+                print_json(&torrents);
+                return Ok(());
+            }
+            let mut rows: Vec<Map<String, Value>> = torrents
+                .into_iter()
+                .map(|(id, torrent)| {
+                    let mut row = to_map(&torrent);
+                    row.insert("id".to_owned(), Value::String(id));
+                    row.insert(
+                        "progress".to_owned(),
+                        json!(format!("{:.1}%", torrent.progress)),
+                    );
+                    row.insert("ratio".to_owned(), json!(format!("{:.2}", torrent.ratio)));
+                    row
+                })
+                .collect();
+            rows.sort_by_key(|row| row.get("name").map(Value::to_string));
+            print_table(
+                &["id", "name", "state", "progress", "ratio", "label"],
+                &rows,
+            );
+            Ok(())
+        }
+        Command::Status { id } => {
+            let torrent = client
+                .get_torrent_status(&id)
+                .await?
+                .get_result("get torrent status")?;
+            print_value(&torrent, cli.json);
+            Ok(())
+        }
+        Command::Add(args) => add(&mut client, &args, cli.json).await,
+        Command::Remove { ids, remove_data } => {
+            for id in ids {
+                let removed = client
+                    .remove_torrent(&id, remove_data)
+                    .await?
+                    .get_result("remove torrent")?;
+                if !removed {
+                    return Err(Error {
+                        action: "remove torrent".to_owned(),
+                        domain: Some("Deluge API".to_owned()),
+                        message: format!("Torrent was not removed: {id}"),
+                        ..Error::default()
+                    });
+                }
+            }
+            Ok(())
+        }
+        Command::Pause { ids } => client.pause_torrents(ids).await?.check("pause torrents"),
+        Command::Resume { ids } => client.resume_torrents(ids).await?.check("resume torrents"),
+        Command::Move { destination, ids } => client
+            .move_storage(ids, &destination)
+            .await?
+            .check("move storage"),
+        Command::Label { id, label } => client
+            .set_torrent_label(&id, &label.unwrap_or_default())
+            .await?
+            .check("set torrent label"),
+        Command::Config { command } => config(&mut client, command, cli.json).await,
+    }
+}
+
+async fn hosts(client: &mut DelugeClient, json: bool) -> Result<(), Error> {
+    let hosts = client.get_hosts().await?.get_result("get hosts")?;
+    let mut rows = Vec::new();
+    for host in hosts {
+        let status = client
+            .get_host_status(&host.id)
+            .await?
+            .get_result("get host status")?;
+        let mut row = to_map(&host);
+        row.extend(to_map(&status));
+        rows.push(row);
+    }
+    if json {
+        print_json(&rows);
+    } else {
+        print_table(&["id", "host", "port", "user", "status", "version"], &rows);
+    }
+    Ok(())
+}
+
+async fn connect(client: &mut DelugeClient, host_id: Option<String>) -> Result<(), Error> {
+    let host_id = match host_id {
+        Some(host_id) => host_id,
+        None => client
+            .get_hosts()
+            .await?
+            .get_result("get hosts")?
+            .into_iter()
+            .next()
+            .map(|host| host.id)
+            .ok_or_else(|| Error {
+                action: "connect".to_owned(),
+                domain: Some("Deluge API".to_owned()),
+                message: "No hosts are configured".to_owned(),
+                ..Error::default()
+            })?,
+    };
+    client.connect(&host_id).await?.check("connect")
+}
+
+async fn add(client: &mut DelugeClient, args: &AddArgs, json: bool) -> Result<(), Error> {
+    let options = args.to_options();
+    if args.source.starts_with("magnet:") {
+        let id = client
+            .add_torrent_magnet(&args.source, options)
+            .await?
+            .get_result("add torrent magnet")?;
+        print_value(&json!({ "id": id }), json);
+        return Ok(());
+    }
+    let path = if args.source.starts_with("http://") || args.source.starts_with("https://") {
+        client
+            .download_torrent_from_url(&args.source)
+            .await?
+            .get_result("download torrent from url")?
+    } else {
+        client.upload_torrent(Path::new(&args.source)).await?
+    };
+    let added = client
+        .add_torrents(vec![TorrentPath { path, options }])
+        .await?
+        .get_result("add torrents")?;
+    print_json_or_table(&added, json);
+    Ok(())
+}
+
+async fn config(
+    client: &mut DelugeClient,
+    command: ConfigCommand,
+    json: bool,
+) -> Result<(), Error> {
+    match command {
+        ConfigCommand::Get { keys } if keys.is_empty() => {
+            let config = client.get_config().await?.get_result("get config")?;
+            print_value(&config, json);
+        }
+        ConfigCommand::Get { keys } => {
+            let values = client
+                .get_config_values(keys)
+                .await?
+                .get_result("get config values")?;
+            print_value(&values, json);
+        }
+        ConfigCommand::Set { key, value } => {
+            let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+            let config: CoreConfig = serde_json::from_value(json!({ key.clone(): value }))
+                .map_err(|e| Error {
+                    action: "parse config value".to_owned(),
+                    domain: Some("deserialization".to_owned()),
+                    message: format!("{key}: {e}"),
+                    ..Error::default()
+                })?;
+            let response: Response<()> = client.set_config(config).await?;
+            response.check("set config")?;
+        }
+    }
+    Ok(())
+}
+
+/// Print a value as JSON or as one field per line
+fn print_value<T: Serialize>(value: &T, json: bool) {
+    if json {
+        print_json(value);
+    } else {
+        print_fields(&to_map(value));
+    }
+}
+
+/// Print a list as JSON or as a table of its items
+fn print_json_or_table<T: Serialize>(items: &[T], json: bool) {
+    if json {
+        print_json(&items);
+        return;
+    }
+    let rows: Vec<Map<String, Value>> = items.iter().map(to_map).collect();
+    let mut columns: Vec<&str> = rows
+        .iter()
+        .flat_map(|row| row.keys())
+        .map(String::as_str)
+        .collect();
+    columns.sort_unstable();
+    columns.dedup();
+    print_table(&columns, &rows);
+}
+
+fn to_map<T: Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value).expect("value should serialize") {
+        Value::Object(map) => map,
+        value => Map::from_iter([("value".to_owned(), value)]),
+    }
+}
@@ -0,0 +1,101 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Print a value as pretty JSON
+pub fn print_json<T: Serialize>(value: &T) {
+    let json = serde_json::to_string_pretty(value).expect("value should serialize");
+    println!("{json}");
+}
+
+/// Print each field of an object on its own line
+pub fn print_fields(fields: &Map<String, Value>) {
+    let width = fields.keys().map(String::len).max().unwrap_or_default();
+    let mut keys: Vec<&String> = fields.keys().collect();
+    keys.sort();
+    for key in keys {
+        let value = fields.get(key).map(cell).unwrap_or_default();
+        println!("{key:width$}  {value}");
+    }
+}
+
+/// Print rows as a table with a column for each of `columns`
+pub fn print_table(columns: &[&str], rows: &[Map<String, Value>]) {
+    for line in format_table(columns, rows) {
+        println!("{line}");
+    }
+}
+
+fn format_table(columns: &[&str], rows: &[Map<String, Value>]) -> Vec<String> {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|column| row.get(*column).map(cell).unwrap_or_default())
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            cells
+                .iter()
+                .filter_map(|row| row.get(index))
+                .map(|x| x.chars().count())
+                .chain([column.len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let header: Vec<String> = columns.iter().map(|column| column.to_uppercase()).collect();
+    [header]
+        .into_iter()
+        .chain(cells)
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_owned()
+        })
+        .collect()
+}
+
+/// Format a value without the quotes of a JSON string
+fn cell(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn format_table_aligns_columns() {
+        // Arrange
+        let rows: Vec<Map<String, Value>> = [
+            json!({ "id": "abc", "name": "debian", "ratio": 1.5 }),
+            json!({ "id": "d", "name": "a", "ratio": null }),
+        ]
+        .into_iter()
+        .filter_map(|x| x.as_object().cloned())
+        .collect();
+
+        // Act
+        let lines = format_table(&["id", "name", "ratio"], &rows);
+
+        // Assert
+        assert_eq!(
+            lines,
+            vec!["ID   NAME    RATIO", "abc  debian  1.5", "d    a"]
+        );
+    }
+}
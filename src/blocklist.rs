@@ -0,0 +1,131 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+impl DelugeClient {
+    /// Get the Blocklist plugin configuration.
+    ///
+    /// Requires the Blocklist plugin to be enabled.
+    pub async fn get_blocklist_config(&mut self) -> Result<Response<BlocklistConfig>, Error> {
+        self.call("blocklist.get_config", ()).await
+    }
+
+    /// Set the Blocklist plugin configuration.
+    ///
+    /// Only the fields that are set are sent so a partial config can be applied.
+    pub async fn set_blocklist_config(
+        &mut self,
+        config: BlocklistConfig,
+    ) -> Result<Response<()>, Error> {
+        self.call("blocklist.set_config", (config,)).await
+    }
+
+    /// Download and import the blocklist if it is out of date.
+    ///
+    /// Set `force` to import even if the blocklist is up to date.
+    ///
+    /// The request completes once the import has finished. The result is not
+    /// meaningful so use [`Response::check`] rather than [`Response::get_result`].
+    pub async fn check_blocklist_import(&mut self, force: bool) -> Result<Response<Value>, Error> {
+        self.call("blocklist.check_import", (force,)).await
+    }
+
+    /// Get the import status of the blocklist.
+    pub async fn get_blocklist_status(&mut self) -> Result<Response<BlocklistStatus>, Error> {
+        self.call("blocklist.get_status", ()).await
+    }
+
+    /// Set the IP ranges that are never blocked.
+    pub async fn set_blocklist_whitelist(
+        &mut self,
+        whitelist: Vec<String>,
+    ) -> Result<Response<()>, Error> {
+        self.call("blocklist.set_whitelist", (whitelist,)).await
+    }
+}
+
+/// Blocklist plugin configuration
+///
+/// Keys that are not modelled are kept in `other`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct BlocklistConfig {
+    /// URL of the blocklist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Import the blocklist when the plugin is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load_on_start: Option<bool>,
+    /// Days before the blocklist is downloaded again
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub check_after_days: Option<u32>,
+    /// Download timeout in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u32>,
+    /// Number of download attempts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub try_times: Option<u32>,
+    /// IP ranges that are never blocked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub whitelisted: Option<Vec<String>>,
+
+    /// Configuration values that are not modelled
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+/// Import status of the blocklist
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BlocklistStatus {
+    pub state: BlocklistState,
+    /// The blocklist has been imported within `check_after_days`
+    pub up_to_date: bool,
+    /// Number of blocked IP ranges
+    pub num_blocked: u64,
+    /// Number of whitelisted IP ranges
+    pub num_whited: u64,
+    /// Download progress from `0` to `1`
+    pub file_progress: f64,
+    /// URL of the blocklist
+    pub file_url: String,
+    /// Size of the blocklist in bytes
+    pub file_size: u64,
+    /// Unix timestamp of the last import
+    pub file_date: f64,
+    /// Format of the blocklist and its compression
+    pub file_type: String,
+    /// IP ranges that are never blocked
+    pub whitelisted: Vec<String>,
+}
+
+#[derive(Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub enum BlocklistState {
+    Downloading,
+    Importing,
+    Idle,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::MockServer;
+    use log::trace;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn get_blocklist_status() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_blocklist_status().await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let _result = response.get_result("get_blocklist_status")?;
+        Ok(())
+    }
+}
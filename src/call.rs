@@ -0,0 +1,147 @@
+use crate::client::{check_id, deserialize_result};
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// A Deluge JSON-RPC method
+///
+/// Implement this to call a method that does not have a wrapper yet, for example
+/// a method exported by a third-party plugin.
+///
+/// # Examples
+/// ```
+/// use deluge_api::RpcMethod;
+///
+/// struct GetLibtorrentVersion;
+///
+/// impl RpcMethod for GetLibtorrentVersion {
+///     type Params = ();
+///     type Output = String;
+///     const METHOD: &'static str = "core.get_libtorrent_version";
+///
+///     fn params(self) -> Self::Params {}
+/// }
+/// ```
+pub trait RpcMethod {
+    /// Parameters of the method
+    ///
+    /// Must serialize to a JSON array, typically a tuple, or `()` for no parameters.
+    type Params: Serialize;
+
+    /// Result of the method
+    type Output: DeserializeOwned + Serialize;
+
+    /// Name of the method including its namespace
+    ///
+    /// # Examples
+    /// - `core.get_torrents_status`
+    /// - `label.get_labels`
+    const METHOD: &'static str;
+
+    /// Get the parameters to send
+    fn params(self) -> Self::Params;
+}
+
+impl DelugeClient {
+    /// Call any Deluge JSON-RPC method.
+    ///
+    /// `params` must serialize to a JSON array, typically a tuple such as `(id,)`,
+    /// or `()` for no parameters.
+    ///
+    /// Returns an error with the [`ID_MISMATCH`](crate::ID_MISMATCH) domain if the
+    /// response is not for this request.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn call<P: Serialize, R: DeserializeOwned>(
+        &mut self,
+        method: &str,
+        params: P,
+    ) -> Result<Response<R>, Error> {
+        let id = self.next_id();
+        let params = to_params(method, params)?;
+        let response = self.transport.send(id, method, params).await?;
+        check_id(method, id, &response)?;
+        deserialize_result(method, response)
+    }
+
+    /// Call a Deluge JSON-RPC method defined by an [`RpcMethod`].
+    pub async fn call_method<M: RpcMethod>(
+        &mut self,
+        method: M,
+    ) -> Result<Response<M::Output>, Error> {
+        self.call(M::METHOD, method.params()).await
+    }
+}
+
+/// Serialize the parameters of a method to a JSON array
+pub(crate) fn to_params<P: Serialize>(method: &str, params: P) -> Result<Value, Error> {
+    let params = serde_json::to_value(params).map_err(|e| Error {
+        action: format!("serialize params of {method} request"),
+        domain: Some("serialization".to_owned()),
+        message: e.to_string(),
+        ..Error::default()
+    })?;
+    match params {
+        Value::Null => Ok(Value::Array(Vec::new())),
+        Value::Array(_) => Ok(params),
+        _ => Err(Error {
+            action: format!("serialize params of {method} request"),
+            domain: Some("serialization".to_owned()),
+            message: "Params must serialize to an array".to_owned(),
+            ..Error::default()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockServer, MockTorrent};
+    use log::trace;
+    use rogue_logging::Error;
+    use serde_json::json;
+
+    struct GetSessionState;
+
+    impl RpcMethod for GetSessionState {
+        type Params = ();
+        type Output = Vec<String>;
+        const METHOD: &'static str = "core.get_session_state";
+
+        fn params(self) -> Self::Params {}
+    }
+
+    #[tokio::test]
+    async fn call_method() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        server
+            .state()
+            .torrents
+            .insert("a".repeat(40), MockTorrent::default());
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.call_method(GetSessionState).await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_session_state")?;
+        assert!(!result.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn to_params_arrays() {
+        assert_eq!(to_params("test", ()).ok(), Some(json!([])));
+        assert_eq!(to_params("test", ("a",)).ok(), Some(json!(["a"])));
+        assert_eq!(to_params("test", ("a", 1)).ok(), Some(json!(["a", 1])));
+        assert!(to_params("test", "a").is_err());
+    }
+}
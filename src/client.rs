@@ -1,110 +1,107 @@
-use crate::Response;
-#[cfg(test)]
-use crate::{DelugeClientFactory, DelugeClientOptions};
+use crate::{Response, Transport};
 use colored::Colorize;
 use log::*;
-use rand::Rng;
-use reqwest::cookie::Jar;
-use reqwest::Client;
 use rogue_logging::Error;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
-use std::sync::Arc;
-use std::time::{Duration, SystemTime};
-use tower::limit::RateLimit;
-use tower::ServiceExt;
+
+/// Domain of the [`Error`] returned when the id of a response does not match
+/// the id of its request
+pub const ID_MISMATCH: &str = "response id";
 
 /// A client for the Deluge API
 ///
 /// Created by an [`DelugeClientFactory`]
 pub struct DelugeClient {
-    pub api_url: String,
     pub password: String,
-    pub cookies: Arc<Jar>,
-    pub client: RateLimit<Client>,
+    /// Sends the calls of every API method
+    pub transport: Box<dyn Transport>,
+    /// Check the free space before [`DelugeClient::add_torrents`]
+    pub free_space_guard: bool,
+    /// Id of the most recent request
+    ///
+    /// Ids increase monotonically so each request of a client can be traced.
+    pub(crate) last_id: u32,
 }
 
 impl DelugeClient {
-    #[cfg(test)]
-    pub(crate) fn from_options(options: DelugeClientOptions) -> DelugeClient {
-        let factory = DelugeClientFactory { options };
-        factory.create()
-    }
-
-    pub(crate) async fn request(
-        &mut self,
-        method: &str,
-        data: Value,
-    ) -> Result<reqwest::Response, Error> {
-        trace!("{} request {method}", "Sending".bold());
-        let api_url = self.api_url.clone();
-        let client = self.wait_for_client().await;
-        let start = SystemTime::now();
-        let result = client.post(api_url).json(&data).send().await;
-        let elapsed = start
-            .elapsed()
-            .expect("elapsed should not fail")
-            .as_secs_f64();
-        trace!("{} response after {elapsed:.3}", "Received".bold());
-        result.map_err(|e| Error {
-            action: format!("send {method} request"),
-            domain: Some("Deluge API".to_owned()),
-            message: e.to_string(),
-            ..Error::default()
-        })
-    }
-
-    async fn wait_for_client(&mut self) -> &Client {
-        let start = SystemTime::now();
-        let client = self
-            .client
-            .ready()
-            .await
-            .expect("client should be available")
-            .get_ref();
-        let duration = start.elapsed().expect("duration should not fail");
-        if duration > Duration::from_millis(200) {
-            trace!(
-                "{} {:.3} for rate limiter",
-                "Waited".bold(),
-                duration.as_secs_f64()
-            );
-        }
-        client
+    /// Get the id for the next request
+    pub(crate) fn next_id(&mut self) -> u32 {
+        self.last_id = self.last_id.wrapping_add(1);
+        self.last_id
     }
 }
 
-pub(crate) fn get_random_u32() -> u32 {
-    let mut rng = rand::thread_rng();
-    rng.gen()
+/// Verify the response id matches the request id
+///
+/// Returns an [`Error`] with the [`ID_MISMATCH`] domain if they differ.
+pub(crate) fn check_id<T>(method: &str, id: u32, response: &Response<T>) -> Result<(), Error> {
+    if response.id == usize::try_from(id).ok() {
+        return Ok(());
+    }
+    let actual = response
+        .id
+        .map_or_else(|| "none".to_owned(), |x| x.to_string());
+    warn!(
+        "{} response #{actual} to request {method} #{id}",
+        "Mismatched".bold()
+    );
+    Err(Error {
+        action: format!("correlate response of {method} request"),
+        domain: Some(ID_MISMATCH.to_owned()),
+        message: format!("Response id {actual} does not match request id {id}"),
+        status_code: response.status_code,
+        ..Error::default()
+    })
 }
 
-pub(crate) async fn deserialize_response<T: DeserializeOwned>(
+/// Deserialize the result of an untyped response
+pub(crate) fn deserialize_result<T: DeserializeOwned>(
     method: &str,
-    response: reqwest::Response,
+    response: Response<Value>,
 ) -> Result<Response<T>, Error> {
-    let status_code = Some(response.status().as_u16());
-    let json = response.text().await.map_err(|e| Error {
-        action: format!("get response body of {method} request"),
-        domain: Some("Deluge API".to_owned()),
-        message: e.to_string(),
-        status_code,
-        ..Error::default()
-    })?;
-    match serde_json::from_str::<Response<T>>(&json) {
-        Ok(mut response) => {
-            response.status_code = status_code;
-            Ok(response)
-        }
-        Err(e) => {
-            trace!("{json}");
-            Err(Error {
+    let result = match response.result {
+        Some(result) => Some(serde_json::from_value(result.clone()).map_err(|e| {
+            trace!("{result}");
+            Error {
                 action: format!("deserialize response of Deluge API {method} request"),
                 domain: Some("deserialization".to_owned()),
                 message: e.to_string(),
-                status_code,
+                status_code: response.status_code,
                 ..Error::default()
-            })
-        }
+            }
+        })?),
+        None => None,
+    };
+    Ok(Response {
+        status_code: response.status_code,
+        result,
+        error: response.error,
+        id: response.id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_id_mismatch() {
+        // Arrange
+        let response: Response<bool> = Response {
+            status_code: Some(200),
+            result: Some(true),
+            error: None,
+            id: Some(2),
+        };
+
+        // Act
+        let matched = check_id("auth.login", 2, &response);
+        let mismatched = check_id("auth.login", 3, &response);
+
+        // Assert
+        assert!(matched.is_ok());
+        let error = mismatched.expect_err("should be a mismatch");
+        assert_eq!(error.domain.as_deref(), Some(ID_MISMATCH));
     }
 }
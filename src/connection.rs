@@ -0,0 +1,39 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde_json::Value;
+
+impl DelugeClient {
+    /// Check if the web server is connected to a daemon.
+    ///
+    /// With the daemon transport this is `true` once connected to the daemon.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn connected(&mut self) -> Result<Response<bool>, Error> {
+        self.call("web.connected", ()).await
+    }
+
+    /// Connect the web server to a daemon listed by [`DelugeClient::get_hosts`].
+    ///
+    /// The connection is shared by every session of the web server.
+    ///
+    /// The result differs between Deluge versions so use [`Response::check`].
+    ///
+    /// Only available with the web transport as the daemon has no equivalent.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn connect(&mut self, host_id: &str) -> Result<Response<Value>, Error> {
+        self.call("web.connect", (host_id,)).await
+    }
+
+    /// Disconnect the web server from the daemon.
+    ///
+    /// Only available with the web transport as the daemon has no equivalent.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn disconnect(&mut self) -> Result<Response<bool>, Error> {
+        self.call("web.disconnect", ()).await
+    }
+}
@@ -0,0 +1,314 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+impl DelugeClient {
+    /// Get all the daemon configuration values.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_config(&mut self) -> Result<Response<CoreConfig>, Error> {
+        self.call("core.get_config", ()).await
+    }
+
+    /// Get a single daemon configuration value.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_config_value(&mut self, key: &str) -> Result<Response<Value>, Error> {
+        self.call("core.get_config_value", (key,)).await
+    }
+
+    /// Get the daemon configuration values for the specified keys.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_config_values(
+        &mut self,
+        keys: Vec<String>,
+    ) -> Result<Response<HashMap<String, Value>>, Error> {
+        self.call("core.get_config_values", (keys,)).await
+    }
+
+    /// Set the daemon configuration values.
+    ///
+    /// Only the fields that are set are sent so a partial config can be applied.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn set_config(&mut self, config: CoreConfig) -> Result<Response<()>, Error> {
+        self.call("core.set_config", (config,)).await
+    }
+}
+
+/// Daemon configuration
+///
+/// Keys that are not modelled are kept in `other`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CoreConfig {
+    /// Directory to download the files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_location: Option<String>,
+    /// Move completed downloads to another directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_completed: Option<bool>,
+    /// Path to move completed downloads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_completed_path: Option<String>,
+    /// Copy the `.torrent` file to `torrentfiles_location`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copy_torrent_file: Option<bool>,
+    /// Delete the copied `.torrent` file when the torrent is removed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub del_copy_torrent_file: Option<bool>,
+    /// Directory to copy `.torrent` files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub torrentfiles_location: Option<String>,
+    /// Directory of the plugins
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugins_location: Option<String>,
+    /// Names of the enabled plugins
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled_plugins: Option<Vec<String>>,
+
+    /// Incoming port range as `[ from, to ]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub listen_ports: Option<[u16; 2]>,
+    /// Use a random incoming port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub random_port: Option<bool>,
+    /// Incoming network interface
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub listen_interface: Option<String>,
+    /// Outgoing network interface
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outgoing_interface: Option<String>,
+    /// Outgoing port range as `[ from, to ]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outgoing_ports: Option<[u16; 2]>,
+    /// Use random outgoing ports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub random_outgoing_ports: Option<bool>,
+    /// Port the daemon listens on for RPC clients
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub daemon_port: Option<u16>,
+    /// Allow RPC clients from other hosts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_remote: Option<bool>,
+
+    /// Maximum number of connections, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_connections_global: Option<i64>,
+    /// Maximum upload speed in KiB/s, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_upload_speed: Option<f64>,
+    /// Maximum download speed in KiB/s, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_download_speed: Option<f64>,
+    /// Maximum number of upload slots, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_upload_slots_global: Option<i64>,
+    /// Maximum number of half open connections, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_half_open_connections: Option<i64>,
+    /// Maximum number of connection attempts per second
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_connections_per_second: Option<i64>,
+    /// Maximum number of connections per torrent, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_connections_per_torrent: Option<i64>,
+    /// Maximum number of upload slots per torrent, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_upload_slots_per_torrent: Option<i64>,
+    /// Maximum upload speed per torrent in KiB/s, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_upload_speed_per_torrent: Option<f64>,
+    /// Maximum download speed per torrent in KiB/s, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_download_speed_per_torrent: Option<f64>,
+    /// Include IP overhead in the rate limits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_ip_overhead: Option<bool>,
+    /// Do not apply rate limits to peers on the local network
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_limits_on_local_network: Option<bool>,
+
+    /// Add new torrents to the top of the queue
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_new_to_top: Option<bool>,
+    /// Maximum number of active torrents, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_active_limit: Option<i64>,
+    /// Maximum number of active downloading torrents, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_active_downloading: Option<i64>,
+    /// Maximum number of active seeding torrents, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_active_seeding: Option<i64>,
+    /// Do not count slow torrents towards the active limits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dont_count_slow_torrents: Option<bool>,
+    /// Stop seeding when the share ratio reaches `stop_seed_ratio`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_seed_at_ratio: Option<bool>,
+    /// Share ratio at which to stop seeding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_seed_ratio: Option<f64>,
+    /// Remove the torrent when it stops seeding at the ratio
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove_seed_at_ratio: Option<bool>,
+    /// Share ratio limit for queueing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share_ratio_limit: Option<f64>,
+    /// Seed time ratio limit for queueing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed_time_ratio_limit: Option<f64>,
+    /// Seed time limit for queueing in minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed_time_limit: Option<i64>,
+    /// Automatically manage new torrents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_managed: Option<bool>,
+
+    /// Add new torrents paused
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add_paused: Option<bool>,
+    /// Pre-allocate disk space for new torrents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pre_allocate_storage: Option<bool>,
+    /// Prioritize the first and last pieces of new torrents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prioritize_first_last_pieces: Option<bool>,
+    /// Download files of new torrents sequentially
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequential_download: Option<bool>,
+    /// Enable super seeding for new torrents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub super_seeding: Option<bool>,
+
+    /// Proxy settings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyConfig>,
+
+    /// Incoming encryption policy
+    ///
+    /// `0` forced, `1` enabled, `2` disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enc_in_policy: Option<u8>,
+    /// Outgoing encryption policy
+    ///
+    /// `0` forced, `1` enabled, `2` disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enc_out_policy: Option<u8>,
+    /// Encryption level
+    ///
+    /// `0` handshake, `1` full stream, `2` either
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enc_level: Option<u8>,
+
+    /// Enable the distributed hash table
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dht: Option<bool>,
+    /// Enable `UPnP` port mapping
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upnp: Option<bool>,
+    /// Enable `NAT-PMP` port mapping
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub natpmp: Option<bool>,
+    /// Enable peer exchange
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utpex: Option<bool>,
+    /// Enable local service discovery
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lsd: Option<bool>,
+
+    /// Configuration values that are not modelled
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+/// Daemon proxy configuration
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ProxyConfig {
+    /// Proxy type
+    ///
+    /// `0` none, `1` SOCKS4, `2` SOCKS5, `3` SOCKS5 with auth, `4` HTTP,
+    /// `5` HTTP with auth, `6` I2P
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub proxy_type: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Resolve hostnames through the proxy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_hostnames: Option<bool>,
+    /// Proxy peer connections
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_peer_connections: Option<bool>,
+    /// Proxy tracker connections
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_tracker_connections: Option<bool>,
+    /// Refuse connections that do not go through the proxy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force_proxy: Option<bool>,
+    /// Hide identifying information from peers and trackers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anonymous_mode: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use log::trace;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn get_config() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_config().await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_config")?;
+        assert!(result.download_location.is_some());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_config() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_config_value("download_location").await?;
+        trace!("{}", response.to_json_pretty());
+        let download_location = response.get_result("get_config_value")?;
+        let config = CoreConfig {
+            download_location: download_location.as_str().map(ToOwned::to_owned),
+            ..CoreConfig::default()
+        };
+
+        // Act
+        let response = client.set_config(config).await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        response.check("set_config")?;
+        Ok(())
+    }
+}
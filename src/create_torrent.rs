@@ -0,0 +1,207 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+
+/// Piece length used if [`CreateTorrentOptions::piece_length`] is not set
+pub const DEFAULT_PIECE_LENGTH: u32 = 256 * 1024;
+
+impl DelugeClient {
+    /// Create a torrent from a file or directory on the daemon.
+    ///
+    /// The `.torrent` file is written to [`CreateTorrentOptions::target`] and,
+    /// if [`CreateTorrentOptions::add_to_session`] is set, the torrent is added
+    /// to the session to seed from the directory containing `path`.
+    ///
+    /// The result is `None` for Deluge 2.0 which does not return the created
+    /// file.
+    ///
+    /// The id of an added torrent is not returned. Register for
+    /// `TorrentAddedEvent` with [`DelugeClient::events`] before creating the
+    /// torrent, or find it by name with [`DelugeClient::get_torrents`].
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn create_torrent(
+        &mut self,
+        options: CreateTorrentOptions,
+    ) -> Result<Response<Option<CreatedTorrent>>, Error> {
+        if options.add_to_session && options.target.is_none() {
+            return Err(Error {
+                action: "create torrent".to_owned(),
+                message: "Target is required to add the torrent to the session".to_owned(),
+                ..Error::default()
+            });
+        }
+        let target = options
+            .target
+            .clone()
+            .unwrap_or_else(|| format!("{}.torrent", options.path.trim_end_matches('/')));
+        let tracker = options
+            .trackers
+            .first()
+            .and_then(|tier| tier.first())
+            .cloned()
+            .unwrap_or_default();
+        let trackers = (!options.trackers.is_empty()).then_some(options.trackers);
+        let web_seeds = (!options.web_seeds.is_empty()).then_some(options.web_seeds);
+        let params = (
+            options.path,
+            tracker,
+            options.piece_length.unwrap_or(DEFAULT_PIECE_LENGTH),
+            options.comment,
+            options.target,
+            web_seeds,
+            options.private,
+            options.created_by,
+            trackers,
+            options.add_to_session,
+        );
+        let response: Response<(String, String)> = self.call("core.create_torrent", params).await?;
+        let created = response
+            .result
+            .map(|(file_name, file_dump)| CreatedTorrent {
+                target,
+                file_name,
+                file_dump,
+            });
+        Ok(Response {
+            status_code: response.status_code,
+            result: response.error.is_none().then_some(created),
+            error: response.error,
+            id: response.id,
+        })
+    }
+}
+
+/// Options of [`DelugeClient::create_torrent`]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CreateTorrentOptions {
+    /// File or directory on the daemon to create the torrent from
+    pub path: String,
+    /// Path on the daemon to write the `.torrent` file
+    ///
+    /// Defaults to `path` with a `.torrent` extension.
+    ///
+    /// Required if [`CreateTorrentOptions::add_to_session`] is set as Deluge 2.0
+    /// reads the file back from `target` to add it.
+    pub target: Option<String>,
+    /// Tracker URLs grouped into tiers
+    ///
+    /// The first tracker of the first tier is the announce URL.
+    pub trackers: Vec<Vec<String>>,
+    /// HTTP seed URLs
+    pub web_seeds: Vec<String>,
+    /// Piece length in bytes, must be a power of two
+    ///
+    /// Defaults to [`DEFAULT_PIECE_LENGTH`].
+    pub piece_length: Option<u32>,
+    pub comment: Option<String>,
+    /// Only announce to the trackers, disabling DHT and peer exchange
+    pub private: bool,
+    pub created_by: Option<String>,
+    /// Add the torrent to the session to seed it
+    pub add_to_session: bool,
+}
+
+/// A `.torrent` file created by [`DelugeClient::create_torrent`]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreatedTorrent {
+    /// Path on the daemon of the `.torrent` file
+    pub target: String,
+    /// File name of the `.torrent` file
+    pub file_name: String,
+    /// Base64 encoded content of the `.torrent` file
+    pub file_dump: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn create_torrent() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+        let options = CreateTorrentOptions {
+            path: "/srv/releases/example".to_owned(),
+            target: Some("/srv/torrents/example.torrent".to_owned()),
+            trackers: vec![vec!["https://tracker.example.com/announce".to_owned()]],
+            private: true,
+            add_to_session: true,
+            ..CreateTorrentOptions::default()
+        };
+
+        // Act
+        client.login().await?;
+        let response = client.create_torrent(options).await?;
+
+        // Assert
+        let created = response
+            .get_result("create_torrent")?
+            .expect("should return the created file");
+        assert_eq!(created.target, "/srv/torrents/example.torrent");
+        assert_eq!(created.file_name, "example.torrent");
+        let state = server.state();
+        let params = state
+            .calls
+            .last()
+            .map(|x| x.params.clone())
+            .unwrap_or_default();
+        assert_eq!(
+            params.get(1).and_then(Value::as_str),
+            Some("https://tracker.example.com/announce")
+        );
+        assert_eq!(params.get(2).and_then(Value::as_u64), Some(262_144));
+        assert_eq!(params.get(6).and_then(Value::as_bool), Some(true));
+        let torrent = state.torrents.values().next().expect("should be added");
+        assert_eq!(torrent.name, "example");
+        assert_eq!(torrent.save_path, "/srv/releases");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_torrent_deluge_2_0() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        if let Some(host) = server.state().hosts.first_mut() {
+            host.version = "2.0.5".to_owned();
+        }
+        let mut client = server.client();
+        let options = CreateTorrentOptions {
+            path: "/srv/releases/example".to_owned(),
+            ..CreateTorrentOptions::default()
+        };
+
+        // Act
+        client.login().await?;
+        let response = client.create_torrent(options).await?;
+
+        // Assert
+        assert!(response.get_result("create_torrent")?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn create_torrent_requires_target_to_add() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+        let options = CreateTorrentOptions {
+            path: "/srv/releases/example".to_owned(),
+            add_to_session: true,
+            ..CreateTorrentOptions::default()
+        };
+
+        // Act
+        client.login().await?;
+        let result = client.create_torrent(options).await;
+
+        // Assert
+        assert!(result.is_err());
+        assert!(server.state().torrents.is_empty());
+        Ok(())
+    }
+}
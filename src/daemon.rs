@@ -0,0 +1,1195 @@
+use crate::daemon_web::{translate, Failure, TRANSLATED};
+use crate::rencode::{decode, encode};
+use crate::{Response, Transport};
+use colored::Colorize;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use futures::future::BoxFuture;
+use log::*;
+use rogue_logging::Error;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::io::{split, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+use tokio_rustls::rustls;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::crypto::{
+    ring, verify_tls12_signature, verify_tls13_signature, CryptoProvider,
+};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, SignatureScheme,
+};
+use tokio_rustls::TlsConnector;
+
+/// Default port of the Deluge daemon RPC server
+pub const DEFAULT_DAEMON_PORT: u16 = 58846;
+
+/// Client version sent to `daemon.login`
+const CLIENT_VERSION: &str = "deluge_api";
+const PROTOCOL_VERSION: u8 = 1;
+const HEADER_SIZE: usize = 5;
+const RPC_RESPONSE: u64 = 1;
+pub(crate) const RPC_ERROR: u64 = 2;
+const RPC_EVENT: u64 = 3;
+
+/// Maximum length of the compressed body of a message
+const MAX_BODY_SIZE: usize = 64 * 1024 * 1024;
+
+/// Maximum length of a message once decompressed
+const MAX_MESSAGE_SIZE: usize = 512 * 1024 * 1024;
+
+/// Senders for the replies of requests awaiting a response keyed by request id
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value, Value>>>>>;
+
+/// First id of the requests sent to translate a `web.*` method
+///
+/// Higher than any id of [`DelugeClient`](crate::DelugeClient) so they never
+/// collide.
+const INTERNAL_ID_START: u64 = 1 << 32;
+
+/// Prefix of the paths returned by [`Transport::upload`]
+const UPLOAD_PREFIX: &str = "upload:";
+
+/// Maximum duration `web.get_events` waits for an event
+#[allow(clippy::duration_suboptimal_units)]
+const EVENT_WAIT: Duration = Duration::from_secs(60);
+
+/// Native RPC over TLS to the Deluge daemon
+///
+/// Connects when the first call is sent.
+///
+/// The daemon does not implement the `web.*` methods so those with a daemon
+/// equivalent are translated:
+/// - `auth.login` calls `daemon.login` with the configured username
+/// - `web.register_event_listener` calls `daemon.set_event_interest`
+/// - `web.deregister_event_listener` stops returning the event
+/// - `web.get_events` returns the events pushed by the daemon
+/// - `web.connected` is `true` once connected
+/// - `web.update_ui` calls `core.get_torrents_status`, `core.get_filter_tree`
+///   and the `core` methods of the stats
+/// - `web.get_torrent_status` calls `core.get_torrent_status`
+/// - `web.add_torrents` calls `core.add_torrent_magnet`, `core.add_torrent_url`
+///   or `core.add_torrent_file`
+/// - `web.get_torrent_info` parses the `.torrent` file
+/// - `web.get_plugins` calls `core.get_enabled_plugins` and
+///   `core.get_available_plugins`
+/// - `web.upload_plugin` calls `core.upload_plugin`
+///
+/// Files are read by the client, either from a path on the local file system
+/// or from a path returned by [`Transport::upload`] which keeps the file in
+/// memory.
+///
+/// Every other `web.*` method manages deluge-web itself, for example its
+/// hosts and config, so it returns an error.
+pub struct DaemonTransport {
+    address: String,
+    username: String,
+    certificate: Option<DaemonCertificate>,
+    connection: Option<DaemonConnection>,
+    /// Uploaded files keyed by the path returned by [`Transport::upload`]
+    uploads: HashMap<String, Vec<u8>>,
+}
+
+/// How the TLS certificate of the daemon is verified
+///
+/// The daemon generates a self-signed certificate so it can not be verified
+/// against a certificate authority.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DaemonCertificate {
+    /// Only accept the certificate in a PEM file
+    ///
+    /// The daemon's certificate is `ssl/daemon.cert` in its config directory.
+    Pinned(PathBuf),
+    /// Accept any certificate
+    ///
+    /// The connection is encrypted but the daemon is not authenticated.
+    AcceptAny,
+}
+
+impl DaemonTransport {
+    /// Create a transport for the daemon at `host:port`, `[ipv6]:port`, or
+    /// `host` with the [`DEFAULT_DAEMON_PORT`]
+    ///
+    /// Connecting fails if `certificate` is `None`.
+    #[must_use]
+    pub fn new(address: String, username: String, certificate: Option<DaemonCertificate>) -> Self {
+        Self {
+            address,
+            username,
+            certificate,
+            connection: None,
+            uploads: HashMap::new(),
+        }
+    }
+
+    /// Send a request, connecting first if necessary
+    ///
+    /// If the request fails the connection is dropped so the next request
+    /// reconnects.
+    async fn send_request(
+        &mut self,
+        method: &str,
+        id: u32,
+        params: Value,
+    ) -> Result<Response<Value>, Error> {
+        let result = self.dispatch(method, id, params).await;
+        if result.is_err() {
+            self.connection = None;
+        }
+        let reply = result?;
+        let (result, error) = match reply {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        // The daemon has no status codes so a delivered response is treated as OK
+        Ok(Response {
+            status_code: Some(200),
+            result,
+            error,
+            id: usize::try_from(id).ok(),
+        })
+    }
+
+    async fn dispatch(
+        &mut self,
+        method: &str,
+        id: u32,
+        params: Value,
+    ) -> Result<Result<Value, Value>, Error> {
+        if method.starts_with("web.") && !TRANSLATED.contains(&method) {
+            return Err(Error {
+                action: format!("send {method} request"),
+                domain: Some("Deluge daemon".to_owned()),
+                message: format!(
+                    "{method} has no daemon equivalent so is only available with the web transport"
+                ),
+                ..Error::default()
+            });
+        }
+        let username = self.username.clone();
+        self.connect().await?;
+        let connection = self.connection.as_mut().expect("connection should be set");
+        match method {
+            "auth.login" => {
+                let args = json!([username, first_string(&params)]);
+                let kwargs = json!({ "client_version": CLIENT_VERSION });
+                let reply = connection
+                    .request(u64::from(id), "daemon.login", args, kwargs)
+                    .await?;
+                Ok(reply.map(|_| Value::Bool(true)))
+            }
+            "web.register_event_listener" => {
+                let event = first_string(&params);
+                connection.interests.insert(event.clone());
+                let args = json!([[event]]);
+                let reply = connection
+                    .request(u64::from(id), "daemon.set_event_interest", args, json!({}))
+                    .await?;
+                Ok(reply.map(|_| Value::Null))
+            }
+            "web.deregister_event_listener" => {
+                connection.interests.remove(&first_string(&params));
+                Ok(Ok(Value::Null))
+            }
+            "web.get_events" => {
+                let events = connection.next_events().await?;
+                if events.is_empty() {
+                    Ok(Ok(Value::Null))
+                } else {
+                    Ok(Ok(Value::Array(events)))
+                }
+            }
+            _ if method.starts_with("web.") => {
+                match translate(connection, &self.uploads, method, &params).await {
+                    Ok(result) => Ok(Ok(result)),
+                    Err(Failure::Response(error)) => Ok(Err(error)),
+                    Err(Failure::Transport(error)) => Err(error),
+                }
+            }
+            _ => {
+                connection
+                    .request(u64::from(id), method, params, json!({}))
+                    .await
+            }
+        }
+    }
+
+    async fn connect(&mut self) -> Result<&mut DaemonConnection, Error> {
+        if self.connection.is_none() {
+            let connection = DaemonConnection::connect(&self.address, self.certificate.as_ref());
+            self.connection = Some(connection.await?);
+        }
+        Ok(self.connection.as_mut().expect("connection should be set"))
+    }
+}
+
+impl Transport for DaemonTransport {
+    fn send<'a>(
+        &'a mut self,
+        id: u32,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<Response<Value>, Error>> {
+        Box::pin(self.send_request(method, id, params))
+    }
+
+    /// Keep the file in memory for the translated `web.*` methods that read it
+    fn upload(
+        &mut self,
+        file_name: String,
+        bytes: Vec<u8>,
+    ) -> BoxFuture<'_, Result<String, Error>> {
+        Box::pin(async move {
+            let path = format!("{UPLOAD_PREFIX}{}/{file_name}", self.uploads.len());
+            self.uploads.insert(path.clone(), bytes);
+            Ok(path)
+        })
+    }
+}
+
+fn first_string(params: &Value) -> String {
+    params
+        .pointer("/0")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// An open connection to the daemon RPC server
+///
+/// Responses are correlated to requests by id and events pushed by the daemon
+/// are queued until they are requested.
+pub(crate) struct DaemonConnection {
+    writer: Box<dyn AsyncWrite + Send + Unpin>,
+    pending: Pending,
+    events: mpsc::UnboundedReceiver<Value>,
+    interests: HashSet<String>,
+    reader: JoinHandle<()>,
+    /// Id of the next request sent to translate a `web.*` method
+    next_internal_id: u64,
+}
+
+impl DaemonConnection {
+    /// Connect to `host:port`, `[ipv6]:port`, or `host` with the
+    /// [`DEFAULT_DAEMON_PORT`]
+    async fn connect(
+        address: &str,
+        certificate: Option<&DaemonCertificate>,
+    ) -> Result<Self, Error> {
+        trace!("{} to daemon at {address}", "Connecting".bold());
+        let pinned =
+            match certificate {
+                Some(DaemonCertificate::Pinned(path)) => {
+                    Some(CertificateDer::from_pem_file(path).map_err(|e| Error {
+                        action: format!("read daemon certificate {}", path.display()),
+                        domain: Some("file system".to_owned()),
+                        message: e.to_string(),
+                        ..Error::default()
+                    })?)
+                }
+                Some(DaemonCertificate::AcceptAny) => None,
+                None => return Err(connect_error(
+                    address,
+                    "No daemon certificate is pinned and accepting any certificate is not enabled"
+                        .to_owned(),
+                )),
+            };
+        let (host, port) = split_address(address);
+        let tcp = TcpStream::connect((host, port))
+            .await
+            .map_err(|e| connect_error(address, e.to_string()))?;
+        let provider = Arc::new(ring::default_provider());
+        let verifier = DaemonCertificateVerifier {
+            pinned,
+            provider: provider.clone(),
+        };
+        let config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| connect_error(address, e.to_string()))?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_no_client_auth();
+        let server_name = ServerName::try_from(host.to_owned())
+            .map_err(|e| connect_error(address, e.to_string()))?;
+        let stream = TlsConnector::from(Arc::new(config))
+            .connect(server_name, tcp)
+            .await
+            .map_err(|e| connect_error(address, e.to_string()))?;
+        Ok(Self::new(stream))
+    }
+
+    fn new<S: AsyncRead + AsyncWrite + Send + 'static>(stream: S) -> Self {
+        let (reader, writer) = split(stream);
+        let pending = Arc::new(Mutex::new(HashMap::new()));
+        let (sender, events) = mpsc::unbounded_channel();
+        let reader = tokio::spawn(read_messages(reader, pending.clone(), sender));
+        Self {
+            writer: Box::new(writer),
+            pending,
+            events,
+            interests: HashSet::new(),
+            reader,
+            next_internal_id: INTERNAL_ID_START,
+        }
+    }
+
+    /// Send a request to translate a `web.*` method and get its result
+    pub(crate) async fn call(&mut self, method: &str, args: Value) -> Result<Value, Failure> {
+        let id = self.next_internal_id;
+        self.next_internal_id += 1;
+        self.request(id, method, args, json!({}))
+            .await?
+            .map_err(Failure::Response)
+    }
+
+    /// Send a request and wait for its response
+    ///
+    /// Returns the result, or the error raised by the daemon as the inner error.
+    async fn request(
+        &mut self,
+        id: u64,
+        method: &str,
+        args: Value,
+        kwargs: Value,
+    ) -> Result<Result<Value, Value>, Error> {
+        trace!("{} request {method} #{id}", "Sending".bold());
+        let (sender, receiver) = oneshot::channel();
+        self.pending
+            .lock()
+            .expect("lock should not be poisoned")
+            .insert(id, sender);
+        let start = SystemTime::now();
+        let message = json!([[id, method, args, kwargs]]);
+        write_message(&mut self.writer, &message)
+            .await
+            .map_err(|e| Error {
+                action: format!("send {method} request"),
+                domain: Some("Deluge daemon".to_owned()),
+                message: e.to_string(),
+                ..Error::default()
+            })?;
+        let reply = receiver.await.map_err(|_| Error {
+            action: format!("receive {method} response"),
+            domain: Some("Deluge daemon".to_owned()),
+            message: "Connection closed".to_owned(),
+            ..Error::default()
+        })?;
+        let elapsed = start
+            .elapsed()
+            .expect("elapsed should not fail")
+            .as_secs_f64();
+        trace!("{} response #{id} after {elapsed:.3}", "Received".bold());
+        Ok(reply)
+    }
+
+    /// Wait for events then get every queued event of interest
+    ///
+    /// Returns an empty list if no event arrives within [`EVENT_WAIT`].
+    async fn next_events(&mut self) -> Result<Vec<Value>, Error> {
+        let Ok(first) = timeout(EVENT_WAIT, self.events.recv()).await else {
+            return Ok(Vec::new());
+        };
+        let first = first.ok_or_else(|| Error {
+            action: "receive events".to_owned(),
+            domain: Some("Deluge daemon".to_owned()),
+            message: "Connection closed".to_owned(),
+            ..Error::default()
+        })?;
+        let mut events = vec![first];
+        while let Ok(event) = self.events.try_recv() {
+            events.push(event);
+        }
+        events.retain(|event| {
+            let name = first_string(event);
+            self.interests.contains(&name)
+        });
+        Ok(events)
+    }
+}
+
+impl Drop for DaemonConnection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Read messages until the connection closes
+///
+/// Responses are sent to the pending request with the same id and events are
+/// queued as `[ name, [ args ] ]` in the same format as `web.get_events`.
+async fn read_messages<R: AsyncRead + Unpin>(
+    mut reader: R,
+    pending: Pending,
+    events: mpsc::UnboundedSender<Value>,
+) {
+    loop {
+        let message = match read_message(&mut reader).await {
+            Ok(message) => message,
+            Err(error) => {
+                debug!("{} daemon connection", "Closing".bold());
+                error.log();
+                break;
+            }
+        };
+        let items = message.as_array().cloned().unwrap_or_default();
+        let mut items = items.into_iter();
+        match items.next().and_then(|x| x.as_u64()) {
+            Some(kind @ (RPC_RESPONSE | RPC_ERROR)) => {
+                let id = items.next().and_then(|x| x.as_u64()).unwrap_or_default();
+                let reply = if kind == RPC_RESPONSE {
+                    Ok(items.next().unwrap_or_default())
+                } else {
+                    Err(to_error(items.collect()))
+                };
+                let sender = pending
+                    .lock()
+                    .expect("lock should not be poisoned")
+                    .remove(&id);
+                if let Some(sender) = sender {
+                    let _ = sender.send(reply);
+                } else {
+                    warn!("{} response #{id} with no request", "Ignoring".bold());
+                }
+            }
+            Some(RPC_EVENT) => {
+                let name = items.next().unwrap_or_default();
+                let args = items.next().unwrap_or_else(|| json!([]));
+                trace!("{} event {name}", "Received".bold());
+                let _ = events.send(json!([name, args]));
+            }
+            _ => warn!("{} unexpected daemon message: {message}", "Ignoring".bold()),
+        }
+    }
+    pending.lock().expect("lock should not be poisoned").clear();
+}
+
+/// Convert the `exception_type, exception_args, ...` of an `RPC_ERROR` message
+/// to the same `{ message, code }` shape as the web API
+fn to_error(items: Vec<Value>) -> Value {
+    let mut items = items.into_iter();
+    let exception = items.next().unwrap_or_default();
+    let message = match items.next() {
+        Some(Value::String(message)) => message,
+        Some(Value::Array(args)) => args
+            .iter()
+            .map(|x| x.as_str().map_or_else(|| x.to_string(), str::to_owned))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    let exception = exception.as_str().unwrap_or("Exception");
+    json!({
+        "message": format!("{exception}: {message}"),
+        "code": RPC_ERROR,
+    })
+}
+
+/// Read a zlib compressed rencode message
+///
+/// Each message has a header of a protocol version byte followed by the
+/// length of the body as a big endian `u32`.
+///
+/// Messages longer than [`MAX_BODY_SIZE`], or [`MAX_MESSAGE_SIZE`] once
+/// decompressed, are rejected before they are allocated.
+pub(crate) async fn read_message<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Value, Error> {
+    let mut header = [0_u8; HEADER_SIZE];
+    reader
+        .read_exact(&mut header)
+        .await
+        .map_err(|e| read_error(e.to_string()))?;
+    let [version, length @ ..] = header;
+    if version != PROTOCOL_VERSION {
+        return Err(read_error(format!(
+            "Unsupported protocol version {version}"
+        )));
+    }
+    let length = usize::try_from(u32::from_be_bytes(length)).expect("u32 should fit usize");
+    if length > MAX_BODY_SIZE {
+        return Err(read_error(format!(
+            "Message of {length} bytes exceeds the limit of {MAX_BODY_SIZE} bytes"
+        )));
+    }
+    let mut body = vec![0_u8; length];
+    reader
+        .read_exact(&mut body)
+        .await
+        .map_err(|e| read_error(e.to_string()))?;
+    decode(&decompress(&body, MAX_MESSAGE_SIZE)?)
+}
+
+/// Decompress a zlib body of at most `limit` bytes
+fn decompress(body: &[u8], limit: usize) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::new();
+    let take = u64::try_from(limit)
+        .expect("limit should fit u64")
+        .saturating_add(1);
+    ZlibDecoder::new(body)
+        .take(take)
+        .read_to_end(&mut bytes)
+        .map_err(|e| read_error(e.to_string()))?;
+    if bytes.len() > limit {
+        return Err(read_error(format!(
+            "Decompressed message exceeds the limit of {limit} bytes"
+        )));
+    }
+    Ok(bytes)
+}
+
+/// Write a zlib compressed rencode message
+pub(crate) async fn write_message<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    message: &Value,
+) -> Result<(), io::Error> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&encode(message))?;
+    let body = encoder.finish()?;
+    let length = u32::try_from(body.len()).map_err(io::Error::other)?;
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + body.len());
+    bytes.push(PROTOCOL_VERSION);
+    bytes.extend(length.to_be_bytes());
+    bytes.extend(body);
+    writer.write_all(&bytes).await?;
+    writer.flush().await
+}
+
+/// Split `host:port` or `[ipv6]:port` into host and port
+///
+/// An address without a port, including a bare IPv6 address, has the
+/// [`DEFAULT_DAEMON_PORT`].
+fn split_address(address: &str) -> (&str, u16) {
+    if let Some(rest) = address.strip_prefix('[') {
+        if let Some((host, rest)) = rest.split_once(']') {
+            let port = rest.strip_prefix(':').and_then(|x| x.parse().ok());
+            return (host, port.unwrap_or(DEFAULT_DAEMON_PORT));
+        }
+    }
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => match port.parse() {
+            Ok(port) => (host, port),
+            Err(_) => (address, DEFAULT_DAEMON_PORT),
+        },
+        _ => (address, DEFAULT_DAEMON_PORT),
+    }
+}
+
+fn connect_error(address: &str, message: String) -> Error {
+    Error {
+        action: format!("connect to daemon at {address}"),
+        domain: Some("Deluge daemon".to_owned()),
+        message,
+        ..Error::default()
+    }
+}
+
+fn read_error(message: String) -> Error {
+    Error {
+        action: "read daemon message".to_owned(),
+        domain: Some("Deluge daemon".to_owned()),
+        message,
+        ..Error::default()
+    }
+}
+
+/// Verify the self-signed certificate generated by the daemon
+///
+/// The certificate must equal `pinned`, or any certificate is accepted if it is
+/// `None`. Signatures are still verified so the handshake is valid.
+#[derive(Debug)]
+struct DaemonCertificateVerifier {
+    pinned: Option<CertificateDer<'static>>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for DaemonCertificateVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        match &self.pinned {
+            Some(pinned) if pinned != end_entity => Err(rustls::Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            )),
+            _ => Ok(ServerCertVerified::assertion()),
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::add_torrents::{Options, TorrentPath};
+    use crate::events::DelugeEvent;
+    use crate::get_torrents::FilterOptions;
+    use crate::testing::MockTorrent;
+    use crate::{DelugeClient, DelugeClientFactory, DelugeClientOptions, TransportKind};
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use rcgen::{generate_simple_self_signed, CertifiedKey};
+    use rogue_logging::LoggerBuilder;
+    use std::env::temp_dir;
+    use std::fs::{create_dir_all, write};
+    use std::process;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use tokio::net::TcpListener;
+    use tokio_rustls::rustls::pki_types::PrivatePkcs8KeyDer;
+    use tokio_rustls::rustls::ServerConfig;
+    use tokio_rustls::TlsAcceptor;
+
+    /// Torrent with the files `example/a.txt` and `example/b/c.txt`
+    const TORRENT: &[u8] = b"d8:announce3:url4:infod5:filesld6:lengthi3e4:pathl1:b5:c.txteed6:lengthi2e4:pathl5:a.txteee4:name7:example12:piece lengthi16384e6:pieces0:ee";
+
+    /// Stand-in daemon that accepts one connection
+    struct Daemon {
+        address: String,
+        /// Path of its PEM certificate
+        certificate: String,
+        /// Method and args of every request received
+        calls: Arc<Mutex<Vec<(String, Value)>>>,
+    }
+
+    impl Daemon {
+        /// Get the args of each call of a method
+        fn calls(&self, method: &str) -> Vec<Value> {
+            self.calls
+                .lock()
+                .expect("lock should not be poisoned")
+                .iter()
+                .filter(|(x, _)| x == method)
+                .map(|(_, args)| args.clone())
+                .collect()
+        }
+    }
+
+    async fn start_daemon() -> Daemon {
+        let CertifiedKey { cert, key_pair } =
+            generate_simple_self_signed(vec!["localhost".to_owned()]).expect("should generate");
+        let certificate = write_certificate(&cert.pem());
+        let key = PrivatePkcs8KeyDer::from(key_pair.serialize_der());
+        let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .expect("should support default versions")
+            .with_no_client_auth()
+            .with_single_cert(vec![cert.der().clone()], key.into())
+            .expect("should accept certificate");
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("should bind");
+        let address = listener.local_addr().expect("should have address");
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let received = calls.clone();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.expect("should accept");
+            let Ok(mut stream) = acceptor.accept(tcp).await else {
+                return;
+            };
+            while let Ok(message) = read_message(&mut stream).await {
+                let request = message.pointer("/0").cloned().unwrap_or_default();
+                let id = request.pointer("/0").cloned().unwrap_or_default();
+                let method = request
+                    .pointer("/1")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let args = request.pointer("/2").cloned().unwrap_or_default();
+                received
+                    .lock()
+                    .expect("lock should not be poisoned")
+                    .push((method.to_owned(), args.clone()));
+                if method == "daemon.set_event_interest" {
+                    let event = json!([RPC_EVENT, "TorrentAddedEvent", ["abc", false]]);
+                    write_message(&mut stream, &event)
+                        .await
+                        .expect("should write");
+                }
+                let reply = match respond(method, &args) {
+                    Ok(result) => json!([RPC_RESPONSE, id, result]),
+                    Err((exception, message)) => {
+                        json!([RPC_ERROR, id, exception, [message], {}, ""])
+                    }
+                };
+                write_message(&mut stream, &reply)
+                    .await
+                    .expect("should write");
+            }
+        });
+        Daemon {
+            address: address.to_string(),
+            certificate,
+            calls,
+        }
+    }
+
+    /// Get the result, or exception type and message, of a daemon method
+    fn respond(method: &str, args: &Value) -> Result<Value, (&'static str, &'static str)> {
+        let result = match method {
+            "daemon.login" if *args == json!(["localclient", "secret"]) => json!(10),
+            "daemon.login" => return Err(("BadLoginError", "Password does not match")),
+            "daemon.set_event_interest" | "core.upload_plugin" => json!(true),
+            "core.get_libtorrent_version" => json!("2.0.10"),
+            "core.get_torrents_status" => json!({ "abc": MockTorrent::default().status() }),
+            "core.get_torrent_status" => MockTorrent::default().status(),
+            "core.get_filter_tree" => json!({ "state": [["All", 1], ["Seeding", 1]] }),
+            "core.get_session_status" => json!({
+                "num_peers": 2,
+                "payload_download_rate": 10.0,
+                "payload_upload_rate": 5.0,
+                "download_rate": 12.0,
+                "upload_rate": 6.0,
+                "dht_nodes": 100,
+                "has_incoming_connections": 1,
+            }),
+            "core.get_config_values" => json!({
+                "max_download_speed": -1.0,
+                "max_upload_speed": -1.0,
+                "max_connections_global": 200,
+            }),
+            "core.get_free_space" => json!(1_000),
+            "core.get_external_ip" => json!("127.0.0.1"),
+            "core.add_torrent_file" if args.pointer("/0") == Some(&json!("duplicate.torrent")) => {
+                return Err(("AddTorrentError", "Torrent already in session"));
+            }
+            "core.add_torrent_file" => json!("a".repeat(40)),
+            "core.add_torrent_magnet" => json!("b".repeat(40)),
+            "core.add_torrent_url" => json!("c".repeat(40)),
+            "core.get_enabled_plugins" => json!(["Label"]),
+            "core.get_available_plugins" => json!(["Label", "Stats"]),
+            _ => return Err(("AttributeError", "Unknown method")),
+        };
+        Ok(result)
+    }
+
+    fn write_certificate(pem: &str) -> String {
+        let path = temp_dir().join(format!("deluge-api-{}.cert", mock_id()));
+        write(&path, pem).expect("should write certificate");
+        path.to_string_lossy().to_string()
+    }
+
+    /// Write a file to a new temporary directory so it keeps its name
+    fn write_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let directory = temp_dir().join(format!("deluge-api-{}", mock_id()));
+        create_dir_all(&directory).expect("should create directory");
+        let path = directory.join(name);
+        write(&path, bytes).expect("should write file");
+        path
+    }
+
+    fn mock_id() -> u64 {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        u64::from(process::id()) << 16 | NEXT.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn create_client(
+        host: String,
+        password: &str,
+        certificate: Option<DaemonCertificate>,
+    ) -> DelugeClient {
+        let (daemon_certificate, accept_any) = match certificate {
+            Some(DaemonCertificate::Pinned(path)) => {
+                (Some(path.to_string_lossy().to_string()), None)
+            }
+            Some(DaemonCertificate::AcceptAny) => (None, Some(true)),
+            None => (None, None),
+        };
+        let options = DelugeClientOptions {
+            host,
+            password: password.to_owned(),
+            transport: Some(TransportKind::Daemon),
+            username: Some("localclient".to_owned()),
+            daemon_certificate,
+            accept_any_daemon_certificate: accept_any,
+            ..DelugeClientOptions::default()
+        };
+        DelugeClientFactory { options }.create()
+    }
+
+    #[tokio::test]
+    async fn daemon_transport() -> Result<(), Error> {
+        // Arrange
+        let _ = LoggerBuilder::new().create();
+        let daemon = start_daemon().await;
+        let certificate = DaemonCertificate::Pinned(PathBuf::from(&daemon.certificate));
+        let mut client = create_client(daemon.address.clone(), "secret", Some(certificate));
+
+        // Act
+        let login = client.login().await?;
+        let version: Response<String> = client.call("core.get_libtorrent_version", ()).await?;
+        let unknown: Response<Value> = client.call("core.unknown", ()).await?;
+        client
+            .register_event_listener("TorrentAddedEvent")
+            .await?
+            .check("register event listener")?;
+        let events = client.get_events().await?;
+        let connected = client.connected().await?;
+        let web = client.get_hosts().await;
+
+        // Assert
+        assert!(login.get_result("login")?);
+        assert_eq!(version.id, Some(2));
+        assert_eq!(version.get_result("get version")?, "2.0.10");
+        let error = unknown.check("unknown").expect_err("should be an error");
+        assert!(error.message.contains("AttributeError: Unknown method"));
+        assert_eq!(
+            events.get_result("get events")?,
+            vec![DelugeEvent::TorrentAdded {
+                torrent_id: "abc".to_owned(),
+                from_state: false
+            }]
+        );
+        assert!(connected.get_result("connected")?);
+        let error = web.expect_err("should be an error");
+        assert!(error.message.contains("no daemon equivalent"));
+        assert_eq!(daemon.calls("web.get_hosts"), Vec::<Value>::new());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn daemon_transport_update_ui() -> Result<(), Error> {
+        // Arrange
+        let _ = LoggerBuilder::new().create();
+        let daemon = start_daemon().await;
+        let mut client = create_client(
+            daemon.address.clone(),
+            "secret",
+            Some(DaemonCertificate::AcceptAny),
+        );
+        let filters = FilterOptions {
+            state: Some(vec!["Seeding".to_owned()]),
+            ..FilterOptions::default()
+        };
+
+        // Act
+        client.login().await?;
+        let interface = client.get_interface(filters).await?;
+        let torrents = client.get_torrents(FilterOptions::default()).await?;
+
+        // Assert
+        let interface = interface.get_result("get interface")?;
+        assert!(interface.connected);
+        assert_eq!(interface.torrents.map(|x| x.len()), Some(1));
+        let state = interface.filters.state.expect("should have state");
+        assert_eq!(state.get("Seeding"), Some(1));
+        assert_eq!(interface.stats.num_connections, 2);
+        assert!((interface.stats.download_protocol_rate - 2.0).abs() < f64::EPSILON);
+        assert_eq!(interface.stats.max_num_connections, 200);
+        assert_eq!(interface.stats.free_space, 1_000);
+        assert_eq!(interface.stats.external_ip, "127.0.0.1");
+        let torrents = torrents.get_result("get torrents")?;
+        assert_eq!(
+            torrents.get("abc").map(|x| x.name.as_str()),
+            Some("example")
+        );
+        assert_eq!(
+            daemon.calls("core.get_torrents_status"),
+            vec![json!([{ "state": ["Seeding"] }, []]), json!([{}, []])]
+        );
+        assert_eq!(daemon.calls("core.get_filter_tree").len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn daemon_transport_get_torrent_status() -> Result<(), Error> {
+        // Arrange
+        let _ = LoggerBuilder::new().create();
+        let daemon = start_daemon().await;
+        let mut client = create_client(
+            daemon.address.clone(),
+            "secret",
+            Some(DaemonCertificate::AcceptAny),
+        );
+
+        // Act
+        client.login().await?;
+        let response = client.get_torrent_status("abc").await?;
+
+        // Assert
+        assert_eq!(response.get_result("get torrent status")?.name, "example");
+        assert_eq!(
+            daemon.calls("core.get_torrent_status"),
+            vec![json!(["abc", []])]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn daemon_transport_add_torrents() -> Result<(), Error> {
+        // Arrange
+        let _ = LoggerBuilder::new().create();
+        let daemon = start_daemon().await;
+        let mut client = create_client(
+            daemon.address.clone(),
+            "secret",
+            Some(DaemonCertificate::AcceptAny),
+        );
+        let file = write_file("example.torrent", TORRENT);
+        let duplicate = write_file("duplicate.torrent", TORRENT);
+        let options = Options {
+            download_location: Some("/downloads".to_owned()),
+            ..Options::default()
+        };
+
+        // Act
+        client.login().await?;
+        let upload = client.upload_torrent(&file).await?;
+        let paths = [
+            "magnet:?xt=urn:btih:example".to_owned(),
+            "https://example.com/example.torrent".to_owned(),
+            upload,
+            duplicate.to_string_lossy().to_string(),
+        ];
+        let torrents = paths
+            .into_iter()
+            .map(|path| TorrentPath {
+                path,
+                options: Options {
+                    download_location: options.download_location.clone(),
+                    ..Options::default()
+                },
+            })
+            .collect();
+        let response = client.add_torrents(torrents).await?;
+
+        // Assert
+        let result = response.get_result("add torrents")?;
+        assert_eq!(
+            serde_json::to_value(result).expect("should serialize"),
+            json!([
+                { "added": true, "hash": "b".repeat(40) },
+                { "added": true, "hash": "c".repeat(40) },
+                { "added": true, "hash": "a".repeat(40) },
+                { "added": false, "hash": "AddTorrentError: Torrent already in session" },
+            ])
+        );
+        let options = json!({ "download_location": "/downloads" });
+        assert_eq!(
+            daemon.calls("core.add_torrent_magnet"),
+            vec![json!(["magnet:?xt=urn:btih:example", options])]
+        );
+        assert_eq!(
+            daemon.calls("core.add_torrent_url"),
+            vec![json!(["https://example.com/example.torrent", options])]
+        );
+        assert_eq!(
+            daemon.calls("core.add_torrent_file"),
+            vec![
+                json!(["example.torrent", STANDARD.encode(TORRENT), options]),
+                json!(["duplicate.torrent", STANDARD.encode(TORRENT), options]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn daemon_transport_get_torrent_info() -> Result<(), Error> {
+        // Arrange
+        let _ = LoggerBuilder::new().create();
+        let daemon = start_daemon().await;
+        let mut client = create_client(
+            daemon.address.clone(),
+            "secret",
+            Some(DaemonCertificate::AcceptAny),
+        );
+        client.free_space_guard = true;
+        let file = write_file("example.torrent", TORRENT);
+        let path = file.to_string_lossy().to_string();
+
+        // Act
+        client.login().await?;
+        let info = client.get_torrent_info(&path).await?;
+        let missing = client.get_torrent_info("/missing.torrent").await;
+        let torrent = TorrentPath {
+            path,
+            options: Options::default(),
+        };
+        let added = client.add_torrents(vec![torrent]).await?;
+
+        // Assert
+        let info = info.get_result("get torrent info")?;
+        assert_eq!(info.name, "example");
+        assert_eq!(info.info_hash, "9a193d3b56118bdc858807f2f3a8acf936ddd105");
+        assert_eq!(info.total_size(), 5);
+        assert_eq!(
+            info.files_tree
+                .pointer("/contents/example/contents/b/contents/c.txt/length"),
+            Some(&json!(3))
+        );
+        let error = missing?
+            .check("get missing torrent info")
+            .expect_err("should be an error");
+        assert!(error.message.contains("Failed to read /missing.torrent"));
+        assert_eq!(added.get_result("add torrents")?.len(), 1);
+        assert_eq!(daemon.calls("core.get_free_space"), vec![json!([null])]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn daemon_transport_plugins() -> Result<(), Error> {
+        // Arrange
+        let _ = LoggerBuilder::new().create();
+        let daemon = start_daemon().await;
+        let mut client = create_client(
+            daemon.address.clone(),
+            "secret",
+            Some(DaemonCertificate::AcceptAny),
+        );
+        let file = write_file("Example-1.0-py3.egg", b"egg");
+
+        // Act
+        client.login().await?;
+        let plugins = client.get_plugins().await?;
+        let uploaded = client.upload_plugin(&file).await?;
+
+        // Assert
+        let plugins = plugins.get_result("get plugins")?;
+        assert_eq!(plugins.enabled_plugins, vec!["Label"]);
+        assert_eq!(plugins.available_plugins, vec!["Label", "Stats"]);
+        assert!(uploaded.get_result("upload plugin")?);
+        assert_eq!(
+            daemon.calls("core.upload_plugin"),
+            vec![json!(["Example-1.0-py3.egg", STANDARD.encode(b"egg")])]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn daemon_transport_bad_login() -> Result<(), Error> {
+        // Arrange
+        let _ = LoggerBuilder::new().create();
+        let daemon = start_daemon().await;
+        let mut client = create_client(daemon.address, "wrong", Some(DaemonCertificate::AcceptAny));
+
+        // Act
+        let response = client.login().await?;
+
+        // Assert
+        let error = response.check("login").expect_err("should be an error");
+        assert!(error.message.contains("BadLoginError"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn daemon_transport_wrong_certificate() {
+        // Arrange
+        let _ = LoggerBuilder::new().create();
+        let daemon = start_daemon().await;
+        let CertifiedKey { cert, .. } =
+            generate_simple_self_signed(vec!["localhost".to_owned()]).expect("should generate");
+        let other = DaemonCertificate::Pinned(PathBuf::from(write_certificate(&cert.pem())));
+        let mut client = create_client(daemon.address, "secret", Some(other));
+
+        // Act
+        let result = client.login().await;
+
+        // Assert
+        let error = result.expect_err("should reject certificate");
+        assert!(error.action.contains("connect to daemon"));
+    }
+
+    #[tokio::test]
+    async fn daemon_transport_requires_certificate() {
+        // Arrange
+        let _ = LoggerBuilder::new().create();
+        let daemon = start_daemon().await;
+        let mut client = create_client(daemon.address, "secret", None);
+
+        // Act
+        let result = client.login().await;
+
+        // Assert
+        let error = result.expect_err("should require a certificate option");
+        assert!(error.message.contains("No daemon certificate"));
+    }
+
+    #[tokio::test]
+    async fn read_message_too_long() {
+        // Arrange
+        let length = u32::try_from(MAX_BODY_SIZE + 1).expect("should fit u32");
+        let mut bytes = vec![PROTOCOL_VERSION];
+        bytes.extend(length.to_be_bytes());
+
+        // Act
+        let result = read_message(&mut bytes.as_slice()).await;
+
+        // Assert
+        let error = result.expect_err("should reject the length");
+        assert!(error.message.contains("exceeds the limit"));
+    }
+
+    #[test]
+    fn decompress_too_long() {
+        // Arrange
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&[0_u8; 1025]).expect("should compress");
+        let body = encoder.finish().expect("should compress");
+
+        // Act
+        let within = decompress(&body, 1025);
+        let exceeded = decompress(&body, 1024);
+
+        // Assert
+        assert_eq!(within.map(|x| x.len()).ok(), Some(1025));
+        let error = exceeded.expect_err("should reject the decompressed length");
+        assert!(error.message.contains("exceeds the limit"));
+    }
+
+    #[test]
+    fn split_addresses() {
+        assert_eq!(
+            split_address("localhost"),
+            ("localhost", DEFAULT_DAEMON_PORT)
+        );
+        assert_eq!(split_address("127.0.0.1:1234"), ("127.0.0.1", 1234));
+        assert_eq!(split_address("::1"), ("::1", DEFAULT_DAEMON_PORT));
+        assert_eq!(split_address("[::1]"), ("::1", DEFAULT_DAEMON_PORT));
+        assert_eq!(split_address("[::1]:1234"), ("::1", 1234));
+    }
+}
@@ -0,0 +1,313 @@
+//! Translate the `web.*` methods called by [`DelugeClient`](crate::DelugeClient)
+//! to their daemon equivalents for the [`DaemonTransport`](crate::DaemonTransport)
+//!
+//! deluge-web implements these methods by calling the daemon so each is
+//! translated to the same daemon calls. Paths of torrent and plugin files are
+//! read by the client as there is no web server to read them.
+//!
+//! # See Also
+//! - <https://github.com/deluge-torrent/deluge/blob/develop/deluge/ui/web/json_api.py>
+use crate::bencode::{decode, raw_value, Bencode};
+use crate::daemon::{DaemonConnection, RPC_ERROR};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ring::digest::{digest, SHA1_FOR_LEGACY_USE_ONLY};
+use rogue_logging::Error;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fmt::Write;
+use tokio::fs::read;
+
+/// `web.*` methods with a daemon equivalent
+pub(crate) const TRANSLATED: [&str; 10] = [
+    "web.register_event_listener",
+    "web.deregister_event_listener",
+    "web.get_events",
+    "web.connected",
+    "web.update_ui",
+    "web.get_torrent_status",
+    "web.add_torrents",
+    "web.get_torrent_info",
+    "web.get_plugins",
+    "web.upload_plugin",
+];
+
+/// Session status keys used by `web.update_ui`
+const STATS_KEYS: [&str; 7] = [
+    "num_peers",
+    "payload_download_rate",
+    "payload_upload_rate",
+    "download_rate",
+    "upload_rate",
+    "dht_nodes",
+    "has_incoming_connections",
+];
+
+/// Config keys used by `web.update_ui`
+const CONFIG_KEYS: [&str; 3] = [
+    "max_download_speed",
+    "max_upload_speed",
+    "max_connections_global",
+];
+
+/// Why a translated method failed
+pub(crate) enum Failure {
+    /// Error in the same shape as a web API error, raised by the daemon or by
+    /// reading a file
+    Response(Value),
+    /// Sending a call failed so the connection is dropped
+    Transport(Error),
+}
+
+impl From<Error> for Failure {
+    fn from(error: Error) -> Self {
+        Failure::Transport(error)
+    }
+}
+
+/// Call the daemon equivalent of a `web.*` method
+///
+/// The event methods and `auth.login` are handled by the transport as they
+/// need its state.
+pub(crate) async fn translate(
+    connection: &mut DaemonConnection,
+    uploads: &HashMap<String, Vec<u8>>,
+    method: &str,
+    params: &Value,
+) -> Result<Value, Failure> {
+    match method {
+        "web.connected" => Ok(Value::Bool(true)),
+        "web.update_ui" => update_ui(connection, params).await,
+        "web.get_torrent_status" => {
+            connection
+                .call("core.get_torrent_status", params.clone())
+                .await
+        }
+        "web.add_torrents" => add_torrents(connection, uploads, param(params, 0)).await,
+        "web.get_torrent_info" => {
+            let path = param_str(params, 0);
+            let bytes = read_file(uploads, &path).await?;
+            torrent_info(&bytes).map_err(|e| to_response(&e))
+        }
+        "web.get_plugins" => {
+            let enabled = connection
+                .call("core.get_enabled_plugins", json!([]))
+                .await?;
+            let available = connection
+                .call("core.get_available_plugins", json!([]))
+                .await?;
+            Ok(json!({
+                "enabled_plugins": enabled,
+                "available_plugins": available,
+            }))
+        }
+        "web.upload_plugin" => {
+            let file_name = param_str(params, 0);
+            let bytes = read_file(uploads, &param_str(params, 1)).await?;
+            let args = json!([file_name, STANDARD.encode(bytes)]);
+            connection.call("core.upload_plugin", args).await?;
+            Ok(Value::Bool(true))
+        }
+        _ => Err(Failure::Transport(Error {
+            action: format!("send {method} request"),
+            domain: Some("Deluge daemon".to_owned()),
+            message: format!("{method} is not translated"),
+            ..Error::default()
+        })),
+    }
+}
+
+/// Get the torrents, filter tree and stats as `web.update_ui` does
+async fn update_ui(connection: &mut DaemonConnection, params: &Value) -> Result<Value, Failure> {
+    let keys = param(params, 0);
+    let filters = match param(params, 1) {
+        Value::Null => json!({}),
+        filters => filters.clone(),
+    };
+    let torrents = connection
+        .call("core.get_torrents_status", json!([filters, keys]))
+        .await?;
+    let filter_tree = connection
+        .call("core.get_filter_tree", json!([true, []]))
+        .await?;
+    let status = connection
+        .call("core.get_session_status", json!([STATS_KEYS]))
+        .await?;
+    let config = connection
+        .call("core.get_config_values", json!([CONFIG_KEYS]))
+        .await?;
+    let free_space = connection.call("core.get_free_space", json!([])).await?;
+    let external_ip = connection.call("core.get_external_ip", json!([])).await?;
+    let rate = |key: &str| status.get(key).and_then(Value::as_f64).unwrap_or_default();
+    Ok(json!({
+        "connected": true,
+        "torrents": torrents,
+        "filters": filter_tree,
+        "stats": {
+            "num_connections": status.get("num_peers"),
+            "upload_rate": rate("payload_upload_rate"),
+            "download_rate": rate("payload_download_rate"),
+            "download_protocol_rate": rate("download_rate") - rate("payload_download_rate"),
+            "upload_protocol_rate": rate("upload_rate") - rate("payload_upload_rate"),
+            "dht_nodes": status.get("dht_nodes"),
+            "has_incoming_connections": status.get("has_incoming_connections"),
+            "max_download": config.get("max_download_speed"),
+            "max_upload": config.get("max_upload_speed"),
+            "max_num_connections": config.get("max_connections_global"),
+            "free_space": free_space,
+            "external_ip": external_ip,
+        },
+    }))
+}
+
+/// Add each torrent by magnet URI, URL or file as `web.add_torrents` does
+///
+/// Each result is `[ true, id ]` or `[ false, message ]` if the daemon
+/// refused the torrent.
+async fn add_torrents(
+    connection: &mut DaemonConnection,
+    uploads: &HashMap<String, Vec<u8>>,
+    torrents: &Value,
+) -> Result<Value, Failure> {
+    let mut results = Vec::new();
+    for torrent in torrents.as_array().map(Vec::as_slice).unwrap_or_default() {
+        let path = torrent
+            .get("path")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let options = torrent.get("options").cloned().unwrap_or_else(|| json!({}));
+        let result = if path.starts_with("magnet:") {
+            connection
+                .call("core.add_torrent_magnet", json!([path, options]))
+                .await
+        } else if path.starts_with("http://") || path.starts_with("https://") {
+            connection
+                .call("core.add_torrent_url", json!([path, options]))
+                .await
+        } else {
+            let bytes = read_file(uploads, path).await?;
+            let file_name = path.rsplit('/').next().unwrap_or(path);
+            let args = json!([file_name, STANDARD.encode(bytes), options]);
+            connection.call("core.add_torrent_file", args).await
+        };
+        let result = match result {
+            Ok(Value::String(id)) => json!([true, id]),
+            Ok(_) => json!([false, "Torrent was not added"]),
+            Err(Failure::Response(error)) => json!([false, error.get("message")]),
+            Err(error) => return Err(error),
+        };
+        results.push(result);
+    }
+    Ok(Value::Array(results))
+}
+
+/// Read a file uploaded to the transport or on the local file system
+async fn read_file(uploads: &HashMap<String, Vec<u8>>, path: &str) -> Result<Vec<u8>, Failure> {
+    if let Some(bytes) = uploads.get(path) {
+        return Ok(bytes.clone());
+    }
+    read(path).await.map_err(|e| {
+        to_response(&Error {
+            action: format!("read {path}"),
+            domain: Some("file system".to_owned()),
+            message: e.to_string(),
+            ..Error::default()
+        })
+    })
+}
+
+/// Convert an error of the client to a web API error so the connection is kept
+fn to_response(error: &Error) -> Failure {
+    Failure::Response(json!({
+        "message": format!("Failed to {}: {}", error.action, error.message),
+        "code": RPC_ERROR,
+    }))
+}
+
+/// Get the name, info hash and files of a `.torrent` file in the same format
+/// as `web.get_torrent_info`
+pub(crate) fn torrent_info(bytes: &[u8]) -> Result<Value, Error> {
+    let torrent = decode(bytes)?;
+    let info = torrent.get("info").ok_or_else(|| info_error("no info"))?;
+    let raw_info = raw_value(bytes, "info")?.ok_or_else(|| info_error("no info"))?;
+    let name = info
+        .get("name.utf-8")
+        .or_else(|| info.get("name"))
+        .and_then(Bencode::as_string)
+        .ok_or_else(|| info_error("no name"))?;
+    let files_tree = if let Some(files) = info.get("files").and_then(Bencode::as_list) {
+        let mut contents = Map::new();
+        for (index, file) in files.iter().enumerate() {
+            let path: Vec<String> = file
+                .get("path.utf-8")
+                .or_else(|| file.get("path"))
+                .and_then(Bencode::as_list)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Bencode::as_string)
+                .collect();
+            let length = file.get("length").and_then(Bencode::as_int);
+            insert_file(&mut contents, &path, index, length.unwrap_or_default());
+        }
+        json!({
+            "type": "dir",
+            "contents": { name.clone(): { "type": "dir", "contents": contents } }
+        })
+    } else {
+        let length = info.get("length").and_then(Bencode::as_int);
+        json!({
+            "type": "dir",
+            "contents": {
+                name.clone(): { "type": "file", "index": 0, "length": length.unwrap_or_default() }
+            }
+        })
+    };
+    let info_hash = digest(&SHA1_FOR_LEGACY_USE_ONLY, raw_info)
+        .as_ref()
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        });
+    Ok(json!({
+        "name": name,
+        "info_hash": info_hash,
+        "files_tree": files_tree,
+    }))
+}
+
+/// Insert a file into nested directory `contents` by its path components
+fn insert_file(contents: &mut Map<String, Value>, path: &[String], index: usize, length: i64) {
+    match path {
+        [] => {}
+        [file_name] => {
+            let file = json!({ "type": "file", "index": index, "length": length });
+            contents.insert(file_name.clone(), file);
+        }
+        [directory, rest @ ..] => {
+            let entry = contents
+                .entry(directory.clone())
+                .or_insert_with(|| json!({ "type": "dir", "contents": {} }));
+            if let Some(Value::Object(children)) = entry.get_mut("contents") {
+                insert_file(children, rest, index, length);
+            }
+        }
+    }
+}
+
+fn info_error(message: &str) -> Error {
+    Error {
+        action: "read torrent info".to_owned(),
+        domain: Some("deserialization".to_owned()),
+        message: format!("Torrent has {message}"),
+        ..Error::default()
+    }
+}
+
+fn param(params: &Value, index: usize) -> &Value {
+    params.get(index).unwrap_or(&Value::Null)
+}
+
+fn param_str(params: &Value, index: usize) -> String {
+    param(params, index).as_str().unwrap_or_default().to_owned()
+}
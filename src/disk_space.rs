@@ -0,0 +1,272 @@
+use crate::add_torrents::TorrentPath;
+use crate::{DelugeClient, Response};
+use colored::Colorize;
+use log::*;
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+impl DelugeClient {
+    /// Get the free space in bytes at a path on the daemon.
+    ///
+    /// If `path` is `None` the default download location is used.
+    ///
+    /// Deluge returns `-1` if the path is invalid or does not exist.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_free_space(&mut self, path: Option<&str>) -> Result<Response<i64>, Error> {
+        self.call("core.get_free_space", (path,)).await
+    }
+
+    /// Get the size in bytes of a file or directory on the daemon.
+    ///
+    /// Deluge returns `-1` if the path does not exist.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_path_size(&mut self, path: &str) -> Result<Response<i64>, Error> {
+        self.call("core.get_path_size", (path,)).await
+    }
+
+    /// Get the directories on the daemon that complete a partial path.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_completion_paths(
+        &mut self,
+        partial: &str,
+    ) -> Result<Response<CompletionPaths>, Error> {
+        let args = CompletionPaths {
+            completion_text: partial.to_owned(),
+            show_hidden_files: false,
+            paths: Vec::new(),
+        };
+        self.call("core.get_completion_paths", (args,)).await
+    }
+
+    /// Get the name, hash and files of a torrent file on the web server.
+    ///
+    /// The path is typically from [`DelugeClient::upload_torrent`] or
+    /// [`DelugeClient::download_torrent_from_url`].
+    ///
+    /// With the daemon transport the file is read and parsed by the client.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn get_torrent_info(&mut self, path: &str) -> Result<Response<TorrentInfo>, Error> {
+        self.call("web.get_torrent_info", (path,)).await
+    }
+
+    /// Check the torrents fit in the free space of their download location.
+    ///
+    /// The sizes of torrents with the same download location are summed so a
+    /// bulk import is checked as a whole. Space still required by torrents
+    /// already in the session is not considered.
+    ///
+    /// Called by [`DelugeClient::add_torrents`] if
+    /// [`DelugeClient::free_space_guard`] is set.
+    pub async fn check_free_space(&mut self, torrents: &[TorrentPath]) -> Result<(), Error> {
+        let mut sizes: BTreeMap<Option<String>, u64> = BTreeMap::new();
+        for torrent in torrents {
+            let info = self
+                .get_torrent_info(&torrent.path)
+                .await?
+                .get_result("get torrent info")?;
+            *sizes
+                .entry(torrent.options.download_location.clone())
+                .or_default() += info.total_size();
+        }
+        for (location, size) in sizes {
+            let free = self
+                .get_free_space(location.as_deref())
+                .await?
+                .get_result("get free space")?;
+            let location = location.unwrap_or_else(|| "the default download location".to_owned());
+            let Ok(free) = u64::try_from(free) else {
+                return Err(Error {
+                    action: "check free space".to_owned(),
+                    domain: Some("disk space".to_owned()),
+                    message: format!("Free space unknown at {location}"),
+                    ..Error::default()
+                });
+            };
+            if size > free {
+                warn!(
+                    "{} to add {size} bytes of torrents to {location}",
+                    "Refusing".bold()
+                );
+                return Err(Error {
+                    action: "check free space".to_owned(),
+                    domain: Some("disk space".to_owned()),
+                    message: format!(
+                        "Torrents require {size} bytes but only {free} bytes are free at {location}"
+                    ),
+                    ..Error::default()
+                });
+            }
+            trace!(
+                "{} {size} of {free} bytes free at {location}",
+                "Checked".bold()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Arguments and result of `core.get_completion_paths`
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CompletionPaths {
+    /// Partial path to complete
+    pub completion_text: String,
+    pub show_hidden_files: bool,
+    /// Directories starting with `completion_text`
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+/// Torrent file on the web server
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TorrentInfo {
+    pub name: String,
+    pub info_hash: String,
+    /// Nested directories and files with their `length` in bytes
+    pub files_tree: Value,
+}
+
+impl TorrentInfo {
+    /// Sum the length of every file
+    #[must_use]
+    pub fn total_size(&self) -> u64 {
+        total_length(&self.files_tree)
+    }
+}
+
+fn total_length(node: &Value) -> u64 {
+    if let Some(length) = node.get("length").and_then(Value::as_u64) {
+        return length;
+    }
+    node.get("contents")
+        .and_then(Value::as_object)
+        .map(|contents| contents.values().map(total_length).sum())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::add_torrents::Options;
+    use crate::testing::MockServer;
+    use crate::DelugeClientFactory;
+    use serde_json::json;
+
+    fn torrent(name: &str) -> TorrentPath {
+        TorrentPath {
+            path: format!("/tmp/delugeweb-mock/{name}.torrent"),
+            options: Options {
+                download_location: Some("/downloads".to_owned()),
+                ..Options::default()
+            },
+        }
+    }
+
+    #[test]
+    fn torrent_info_total_size() {
+        // Arrange
+        let info = TorrentInfo {
+            name: "example".to_owned(),
+            info_hash: "a".repeat(40),
+            files_tree: json!({
+                "type": "dir",
+                "contents": {
+                    "example": {
+                        "type": "dir",
+                        "contents": {
+                            "a.txt": { "type": "file", "index": 0, "length": 100 },
+                            "b": {
+                                "type": "dir",
+                                "contents": {
+                                    "c.txt": { "type": "file", "index": 1, "length": 20 }
+                                }
+                            }
+                        }
+                    }
+                }
+            }),
+        };
+
+        // Act
+        let size = info.total_size();
+
+        // Assert
+        assert_eq!(size, 120);
+    }
+
+    #[tokio::test]
+    async fn disk_space() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        server.state().free_space = 1_000;
+        let mut client = server.client();
+
+        // Act
+        client.login().await?;
+        let free = client.get_free_space(Some("/downloads")).await?;
+        let size = client.get_path_size("/missing").await?;
+        let paths = client.get_completion_paths("/down").await?;
+
+        // Assert
+        assert_eq!(free.get_result("get_free_space")?, 1_000);
+        assert_eq!(size.get_result("get_path_size")?, -1);
+        let paths = paths.get_result("get_completion_paths")?;
+        assert_eq!(paths.paths, vec!["/downloads/"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_torrents_free_space_guard() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        // Each mock torrent is 1 MiB
+        server.state().free_space = 1_572_864;
+        let mut options = server.options();
+        options.free_space_guard = Some(true);
+        let mut client = DelugeClientFactory { options }.create();
+
+        // Act
+        client.login().await?;
+        let refused = client.add_torrents(vec![torrent("a"), torrent("b")]).await;
+        let added = client.add_torrents(vec![torrent("a")]).await?;
+
+        // Assert
+        let error = refused.expect_err("should exceed free space");
+        assert_eq!(error.domain.as_deref(), Some("disk space"));
+        assert_eq!(added.get_result("add_torrents")?.len(), 1);
+        assert_eq!(server.state().torrents.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_torrents_free_space_unknown() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        server.state().free_space = -1;
+        let mut options = server.options();
+        options.free_space_guard = Some(true);
+        let mut client = DelugeClientFactory { options }.create();
+
+        // Act
+        client.login().await?;
+        let free = client.get_free_space(Some("/new")).await?;
+        let refused = client.add_torrents(vec![torrent("a")]).await;
+
+        // Assert
+        assert_eq!(free.get_result("get_free_space")?, -1);
+        let error = refused.expect_err("should refuse unknown free space");
+        assert_eq!(error.domain.as_deref(), Some("disk space"));
+        assert!(error.message.contains("Free space unknown at /downloads"));
+        assert!(server.state().torrents.is_empty());
+        Ok(())
+    }
+}
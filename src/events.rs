@@ -0,0 +1,470 @@
+use crate::{DelugeClient, Response, State};
+use colored::Colorize;
+use futures::stream::{unfold, Stream};
+use log::*;
+use rogue_logging::Error;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+
+impl DelugeClient {
+    /// Register for an event so it is returned by [`DelugeClient::get_events`].
+    ///
+    /// # Examples
+    /// - `TorrentAddedEvent`
+    /// - `TorrentFinishedEvent`
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn register_event_listener(&mut self, event: &str) -> Result<Response<()>, Error> {
+        self.call("web.register_event_listener", (event,)).await
+    }
+
+    /// Stop receiving an event.
+    pub async fn deregister_event_listener(&mut self, event: &str) -> Result<Response<()>, Error> {
+        self.call("web.deregister_event_listener", (event,)).await
+    }
+
+    /// Get the registered events that occurred since the last call.
+    ///
+    /// If no events are queued Deluge waits up to five minutes for one to occur.
+    /// The result is `null` if none did so use [`Response::check`] and treat a
+    /// missing result as empty.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn get_events(&mut self) -> Result<Response<Vec<DelugeEvent>>, Error> {
+        self.call("web.get_events", ()).await
+    }
+
+    /// Register for events and stream them as they occur.
+    ///
+    /// The stream owns the client and long-polls [`DelugeClient::get_events`]
+    /// until it is dropped. Create a second client with
+    /// [`DelugeClientFactory`](crate::DelugeClientFactory) to make other calls
+    /// while listening.
+    ///
+    /// A failed poll is returned as an error, for example if the session
+    /// expires. The next poll logs in and registers the events again before
+    /// resuming, so events that occur in between are missed.
+    pub async fn events(
+        mut self,
+        events: &[&str],
+    ) -> Result<impl Stream<Item = Result<DelugeEvent, Error>>, Error> {
+        let events: Vec<String> = events.iter().map(|&x| x.to_owned()).collect();
+        self.register_event_listeners(&events).await?;
+        let state = EventState {
+            client: self,
+            events,
+            failed: false,
+            queue: VecDeque::new(),
+        };
+        Ok(unfold(state, |mut state| async move {
+            loop {
+                if let Some(event) = state.queue.pop_front() {
+                    return Some((Ok(event), state));
+                }
+                if state.failed {
+                    if let Err(error) = state.resume().await {
+                        return Some((Err(error), state));
+                    }
+                    state.failed = false;
+                }
+                let result = state.client.get_events().await.and_then(|response| {
+                    response.check("get events")?;
+                    Ok(response.result.unwrap_or_default())
+                });
+                match result {
+                    Ok(events) => state.queue.extend(events),
+                    Err(error) => {
+                        state.failed = true;
+                        return Some((Err(error), state));
+                    }
+                }
+            }
+        }))
+    }
+
+    async fn register_event_listeners(&mut self, events: &[String]) -> Result<(), Error> {
+        for event in events {
+            self.register_event_listener(event)
+                .await?
+                .check("register event listener")?;
+        }
+        Ok(())
+    }
+}
+
+struct EventState {
+    client: DelugeClient,
+    events: Vec<String>,
+    /// The previous poll failed so log in again before the next
+    failed: bool,
+    queue: VecDeque<DelugeEvent>,
+}
+
+impl EventState {
+    async fn resume(&mut self) -> Result<(), Error> {
+        debug!("{} event stream", "Resuming".bold());
+        if !self.client.login().await?.get_result("login")? {
+            return Err(Error {
+                action: "resume event stream".to_owned(),
+                domain: Some("Deluge API".to_owned()),
+                message: "Login failed".to_owned(),
+                ..Error::default()
+            });
+        }
+        self.client.register_event_listeners(&self.events).await
+    }
+}
+
+/// An event emitted by Deluge
+///
+/// # See Also
+/// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+#[derive(Debug, PartialEq, Serialize)]
+pub enum DelugeEvent {
+    TorrentAdded {
+        torrent_id: String,
+        /// Torrent was loaded from the saved state rather than newly added
+        from_state: bool,
+    },
+    PreTorrentRemoved {
+        torrent_id: String,
+    },
+    TorrentRemoved {
+        torrent_id: String,
+    },
+    TorrentStateChanged {
+        torrent_id: String,
+        state: State,
+    },
+    TorrentTrackerStatus {
+        torrent_id: String,
+        status: String,
+    },
+    TorrentQueueChanged,
+    TorrentFolderRenamed {
+        torrent_id: String,
+        old: String,
+        new: String,
+    },
+    TorrentFileRenamed {
+        torrent_id: String,
+        index: u32,
+        name: String,
+    },
+    TorrentFinished {
+        torrent_id: String,
+    },
+    TorrentResumed {
+        torrent_id: String,
+    },
+    TorrentFileCompleted {
+        torrent_id: String,
+        index: u32,
+    },
+    TorrentStorageMoved {
+        torrent_id: String,
+        path: String,
+    },
+    CreateTorrentProgress {
+        piece_count: u64,
+        num_pieces: u64,
+    },
+    NewVersionAvailable {
+        new_release: String,
+    },
+    SessionStarted,
+    SessionPaused,
+    SessionResumed,
+    ConfigValueChanged {
+        key: String,
+        value: Value,
+    },
+    PluginEnabled {
+        plugin_name: String,
+    },
+    PluginDisabled {
+        plugin_name: String,
+    },
+    ClientDisconnected {
+        session_id: u64,
+    },
+    ExternalIp {
+        external_ip: String,
+    },
+    /// An event that is not modelled, for example one emitted by a plugin
+    Other {
+        name: String,
+        args: Vec<Value>,
+    },
+}
+
+impl DelugeEvent {
+    /// Create an event from its name and arguments
+    ///
+    /// Events that are not modelled, or have unexpected arguments, are returned
+    /// as [`DelugeEvent::Other`].
+    #[must_use]
+    pub fn from_args(name: &str, args: Vec<Value>) -> Self {
+        Self::parse(name, args.clone()).unwrap_or_else(|| DelugeEvent::Other {
+            name: name.to_owned(),
+            args,
+        })
+    }
+
+    fn parse(name: &str, args: Vec<Value>) -> Option<Self> {
+        let event = match name {
+            "TorrentAddedEvent" => {
+                let (torrent_id, from_state) = from_args(args)?;
+                DelugeEvent::TorrentAdded {
+                    torrent_id,
+                    from_state,
+                }
+            }
+            "PreTorrentRemovedEvent" => {
+                let (torrent_id,) = from_args(args)?;
+                DelugeEvent::PreTorrentRemoved { torrent_id }
+            }
+            "TorrentRemovedEvent" => {
+                let (torrent_id,) = from_args(args)?;
+                DelugeEvent::TorrentRemoved { torrent_id }
+            }
+            "TorrentStateChangedEvent" => {
+                let (torrent_id, state) = from_args(args)?;
+                DelugeEvent::TorrentStateChanged { torrent_id, state }
+            }
+            "TorrentTrackerStatusEvent" => {
+                let (torrent_id, status) = from_args(args)?;
+                DelugeEvent::TorrentTrackerStatus { torrent_id, status }
+            }
+            "TorrentQueueChangedEvent" => DelugeEvent::TorrentQueueChanged,
+            "TorrentFolderRenamedEvent" => {
+                let (torrent_id, old, new) = from_args(args)?;
+                DelugeEvent::TorrentFolderRenamed {
+                    torrent_id,
+                    old,
+                    new,
+                }
+            }
+            "TorrentFileRenamedEvent" => {
+                let (torrent_id, index, name) = from_args(args)?;
+                DelugeEvent::TorrentFileRenamed {
+                    torrent_id,
+                    index,
+                    name,
+                }
+            }
+            "TorrentFinishedEvent" => {
+                let (torrent_id,) = from_args(args)?;
+                DelugeEvent::TorrentFinished { torrent_id }
+            }
+            "TorrentResumedEvent" => {
+                let (torrent_id,) = from_args(args)?;
+                DelugeEvent::TorrentResumed { torrent_id }
+            }
+            "TorrentFileCompletedEvent" => {
+                let (torrent_id, index) = from_args(args)?;
+                DelugeEvent::TorrentFileCompleted { torrent_id, index }
+            }
+            "TorrentStorageMovedEvent" => {
+                let (torrent_id, path) = from_args(args)?;
+                DelugeEvent::TorrentStorageMoved { torrent_id, path }
+            }
+            "CreateTorrentProgressEvent" => {
+                let (piece_count, num_pieces) = from_args(args)?;
+                DelugeEvent::CreateTorrentProgress {
+                    piece_count,
+                    num_pieces,
+                }
+            }
+            "NewVersionAvailableEvent" => {
+                let (new_release,) = from_args(args)?;
+                DelugeEvent::NewVersionAvailable { new_release }
+            }
+            "SessionStartedEvent" => DelugeEvent::SessionStarted,
+            "SessionPausedEvent" => DelugeEvent::SessionPaused,
+            "SessionResumedEvent" => DelugeEvent::SessionResumed,
+            "ConfigValueChangedEvent" => {
+                let (key, value) = from_args(args)?;
+                DelugeEvent::ConfigValueChanged { key, value }
+            }
+            "PluginEnabledEvent" => {
+                let (plugin_name,) = from_args(args)?;
+                DelugeEvent::PluginEnabled { plugin_name }
+            }
+            "PluginDisabledEvent" => {
+                let (plugin_name,) = from_args(args)?;
+                DelugeEvent::PluginDisabled { plugin_name }
+            }
+            "ClientDisconnectedEvent" => {
+                let (session_id,) = from_args(args)?;
+                DelugeEvent::ClientDisconnected { session_id }
+            }
+            "ExternalIPEvent" => {
+                let (external_ip,) = from_args(args)?;
+                DelugeEvent::ExternalIp { external_ip }
+            }
+            _ => return None,
+        };
+        Some(event)
+    }
+}
+
+fn from_args<T: DeserializeOwned>(args: Vec<Value>) -> Option<T> {
+    serde_json::from_value(Value::Array(args)).ok()
+}
+
+impl<'de> Deserialize<'de> for DelugeEvent {
+    /// Deserialize from `[ name, [ args ] ]` format
+    #[allow(clippy::absolute_paths)]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (name, args): (String, Vec<Value>) = Deserialize::deserialize(deserializer)?;
+        Ok(DelugeEvent::from_args(&name, args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::add_torrents::{Options, TorrentPath};
+    use crate::testing::MockServer;
+    use futures::StreamExt;
+    use rogue_logging::Error;
+    use serde_json::json;
+    use std::pin::pin;
+
+    #[tokio::test]
+    async fn register_event_listener() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client
+            .register_event_listener("TorrentFinishedEvent")
+            .await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        response.check("register_event_listener")?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn events_while_calling_other_client() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut listener = server.client();
+        let mut client = server.client();
+        listener.login().await?;
+        client.login().await?;
+        let path = "/tmp/delugeweb-mock/example.torrent".to_owned();
+
+        // Act
+        let events = listener.events(&["TorrentAddedEvent"]).await?;
+        let mut events = pin!(events);
+        let response = client
+            .add_torrents(vec![TorrentPath {
+                path,
+                options: Options::default(),
+            }])
+            .await?;
+        let event = events.next().await.transpose()?;
+
+        // Assert
+        response.get_result("add_torrents")?;
+        assert!(matches!(
+            event,
+            Some(DelugeEvent::TorrentAdded {
+                from_state: false,
+                ..
+            })
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn events_resume_after_session_expires() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut listener = server.client();
+        let mut client = server.client();
+        listener.login().await?;
+        let path = "/tmp/delugeweb-mock/example.torrent".to_owned();
+
+        // Act
+        let events = listener.events(&["TorrentAddedEvent"]).await?;
+        let mut events = pin!(events);
+        server.state().expire_sessions();
+        let expired = events.next().await;
+        client.login().await?;
+        client
+            .add_torrents(vec![TorrentPath {
+                path,
+                options: Options::default(),
+            }])
+            .await?
+            .get_result("add_torrents")?;
+        let resumed = events.next().await.transpose()?;
+
+        // Assert
+        let error = expired
+            .expect("should not end")
+            .expect_err("should fail while expired");
+        assert!(error.message.contains("Not authenticated"));
+        assert!(matches!(resumed, Some(DelugeEvent::TorrentAdded { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_events() {
+        // Arrange
+        let json = json!([
+            ["TorrentAddedEvent", ["abc", false]],
+            ["TorrentStateChangedEvent", ["abc", "Seeding"]],
+            ["TorrentQueueChangedEvent", []],
+            ["ConfigValueChangedEvent", ["max_upload_speed", 100.0]],
+            ["LabelChangedEvent", ["abc", "linux"]],
+            ["TorrentFinishedEvent", []]
+        ]);
+
+        // Act
+        let events: Vec<DelugeEvent> = serde_json::from_value(json).expect("should deserialize");
+
+        // Assert
+        assert_eq!(
+            events,
+            vec![
+                DelugeEvent::TorrentAdded {
+                    torrent_id: "abc".to_owned(),
+                    from_state: false
+                },
+                DelugeEvent::TorrentStateChanged {
+                    torrent_id: "abc".to_owned(),
+                    state: State::Seeding
+                },
+                DelugeEvent::TorrentQueueChanged,
+                DelugeEvent::ConfigValueChanged {
+                    key: "max_upload_speed".to_owned(),
+                    value: json!(100.0)
+                },
+                DelugeEvent::Other {
+                    name: "LabelChangedEvent".to_owned(),
+                    args: vec![json!("abc"), json!("linux")]
+                },
+                DelugeEvent::Other {
+                    name: "TorrentFinishedEvent".to_owned(),
+                    args: Vec::new()
+                },
+            ]
+        );
+    }
+}
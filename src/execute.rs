@@ -0,0 +1,100 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+
+impl DelugeClient {
+    /// Get the commands.
+    ///
+    /// Requires the Execute plugin to be enabled.
+    pub async fn get_commands(&mut self) -> Result<Response<Vec<Command>>, Error> {
+        self.call("execute.get_commands", ()).await
+    }
+
+    /// Add a command to run on an event.
+    ///
+    /// The id is generated by Deluge so use [`DelugeClient::get_commands`] to find it.
+    pub async fn add_command(
+        &mut self,
+        event: ExecuteEvent,
+        command: &str,
+    ) -> Result<Response<()>, Error> {
+        self.call("execute.add_command", (event, command)).await
+    }
+
+    /// Remove a command.
+    pub async fn remove_command(&mut self, id: &str) -> Result<Response<()>, Error> {
+        self.call("execute.remove_command", (id,)).await
+    }
+
+    /// Update the event and command of an existing command.
+    pub async fn save_command(
+        &mut self,
+        id: &str,
+        event: ExecuteEvent,
+        command: &str,
+    ) -> Result<Response<()>, Error> {
+        self.call("execute.save_command", (id, event, command))
+            .await
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Command {
+    pub id: String,
+    pub event: ExecuteEvent,
+    pub command: String,
+}
+
+impl<'de> Deserialize<'de> for Command {
+    /// Deserialize from `[ id, event, command ]` format
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (id, event, command) = Deserialize::deserialize(deserializer)?;
+        Ok(Command { id, event, command })
+    }
+}
+
+/// Event that triggers a command
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub enum ExecuteEvent {
+    #[serde(rename = "added")]
+    TorrentAdded,
+    #[serde(rename = "complete")]
+    TorrentComplete,
+    #[serde(rename = "removed")]
+    TorrentRemoved,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use log::trace;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn get_commands() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client
+            .add_command(ExecuteEvent::TorrentComplete, "/usr/local/bin/notify")
+            .await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_commands().await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_commands")?;
+        let command = result.first().expect("should have added command");
+        assert_eq!(command.event, ExecuteEvent::TorrentComplete);
+        assert_eq!(command.command, "/usr/local/bin/notify");
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+impl DelugeClient {
+    /// Get the Extractor plugin configuration.
+    ///
+    /// Requires the Extractor plugin to be enabled.
+    pub async fn get_extractor_config(&mut self) -> Result<Response<ExtractorConfig>, Error> {
+        self.call("extractor.get_config", ()).await
+    }
+
+    /// Set the Extractor plugin configuration.
+    ///
+    /// Only the fields that are set are sent so a partial config can be applied.
+    pub async fn set_extractor_config(
+        &mut self,
+        config: ExtractorConfig,
+    ) -> Result<Response<()>, Error> {
+        self.call("extractor.set_config", (config,)).await
+    }
+}
+
+/// Extractor plugin configuration
+///
+/// Keys that are not modelled are kept in `other`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ExtractorConfig {
+    /// Directory to extract archives to, empty for the download location
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extract_path: Option<String>,
+    /// Extract into a folder named after the torrent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_name_folder: Option<bool>,
+
+    /// Configuration values that are not modelled
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::MockServer;
+    use log::trace;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn get_extractor_config() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_extractor_config().await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_extractor_config")?;
+        assert!(result.use_name_folder.is_some());
+        Ok(())
+    }
+}
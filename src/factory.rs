@@ -1,8 +1,11 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 use crate::DelugeClient;
-use crate::DelugeClientOptions;
+use crate::{
+    DaemonCertificate, DaemonTransport, DelugeClientOptions, Transport, TransportKind, WebTransport,
+};
 use reqwest::cookie::Jar;
 use reqwest::header::{HeaderMap, HeaderValue};
 use reqwest::{header, ClientBuilder};
@@ -17,6 +20,8 @@ pub struct DelugeClientFactory {
 }
 
 impl DelugeClientFactory {
+    /// Create a client with the transport configured by
+    /// [`DelugeClientOptions::transport`]
     #[must_use]
     #[allow(clippy::as_conversions)]
     pub fn create(&self) -> DelugeClient {
@@ -35,11 +40,46 @@ impl DelugeClientFactory {
         let client = tower::ServiceBuilder::new()
             .rate_limit(rate_count, rate_duration)
             .service(client);
+        let transport: Box<dyn Transport> = match self.options.transport.unwrap_or_default() {
+            TransportKind::Web => Box::new(WebTransport {
+                api_url: format!("{}/json", self.options.host),
+                cookies,
+                client,
+                // More could not start within the rate limit window anyway
+                max_concurrent: self.options.rate_limit_count.unwrap_or(DEFAULT_RATE_COUNT),
+            }),
+            TransportKind::Daemon => Box::new(DaemonTransport::new(
+                self.options.host.clone(),
+                self.options.username.clone().unwrap_or_default(),
+                self.get_daemon_certificate(),
+            )),
+        };
         DelugeClient {
-            api_url: format!("{}/json", self.options.host),
-            cookies,
             password: self.options.password.clone(),
-            client,
+            transport,
+            free_space_guard: self.options.free_space_guard.unwrap_or_default(),
+            last_id: 0,
+        }
+    }
+
+    /// Create a client that sends calls with a custom [`Transport`]
+    #[must_use]
+    pub fn create_with_transport<T: Transport + 'static>(&self, transport: T) -> DelugeClient {
+        DelugeClient {
+            password: self.options.password.clone(),
+            transport: Box::new(transport),
+            free_space_guard: self.options.free_space_guard.unwrap_or_default(),
+            last_id: 0,
+        }
+    }
+
+    fn get_daemon_certificate(&self) -> Option<DaemonCertificate> {
+        if let Some(path) = self.options.daemon_certificate.clone() {
+            Some(DaemonCertificate::Pinned(PathBuf::from(path)))
+        } else if self.options.accept_any_daemon_certificate == Some(true) {
+            Some(DaemonCertificate::AcceptAny)
+        } else {
+            None
         }
     }
 
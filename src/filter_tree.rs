@@ -0,0 +1,119 @@
+use crate::get_interface::Counts;
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize, Serializer};
+
+impl DelugeClient {
+    /// Get the number of torrents for each value of each filter category.
+    ///
+    /// Set `show_zero_hits` to include values with no torrents and `hide_cat` to
+    /// exclude categories.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_filter_tree(
+        &mut self,
+        show_zero_hits: bool,
+        hide_cat: Vec<String>,
+    ) -> Result<Response<Counts>, Error> {
+        self.call("core.get_filter_tree", (show_zero_hits, hide_cat))
+            .await
+    }
+}
+
+/// A category torrents can be filtered by
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum FilterCategory {
+    Label,
+    Owner,
+    State,
+    TrackerHost,
+    /// A category defined by a plugin
+    Other(String),
+}
+
+impl FilterCategory {
+    /// Key as used by Deluge
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            FilterCategory::Label => "label",
+            FilterCategory::Owner => "owner",
+            FilterCategory::State => "state",
+            FilterCategory::TrackerHost => "tracker_host",
+            FilterCategory::Other(name) => name,
+        }
+    }
+}
+
+/// Number of torrents for each value of a filter category
+///
+/// Values are kept in the order Deluge returns them.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct FilterCounts(pub Vec<FilterCount>);
+
+impl FilterCounts {
+    /// Get the number of torrents for a value
+    #[must_use]
+    pub fn get(&self, value: &str) -> Option<u64> {
+        self.0.iter().find(|x| x.value == value).map(|x| x.count)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &FilterCount> {
+        self.0.iter()
+    }
+}
+
+impl Serialize for FilterCounts {
+    /// Serialize to the same `[ [ value, count ] ]` format Deluge uses
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.0.iter().map(|x| (&x.value, x.count)))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct FilterCount {
+    pub value: String,
+    pub count: u64,
+}
+
+impl<'de> Deserialize<'de> for FilterCount {
+    /// Deserialize from `[ value, count ]` format
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (value, count) = Deserialize::deserialize(deserializer)?;
+        Ok(FilterCount { value, count })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::MockServer;
+    use log::trace;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn get_filter_tree() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_filter_tree(true, Vec::new()).await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_filter_tree")?;
+        let state = result.state.as_ref().expect("should have state category");
+        assert!(state.get("All").is_some());
+        Ok(())
+    }
+}
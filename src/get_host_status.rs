@@ -1,23 +1,16 @@
-use crate::client::{deserialize_response, get_random_u32};
 use crate::{DelugeClient, Response};
 use rogue_logging::Error;
 use serde::{Deserialize, Serialize, Serializer};
-use serde_json::json;
 
 impl DelugeClient {
     /// Get the current status for the specified host.
     ///
+    /// Only available with the web transport as the daemon has no equivalent.
+    ///
     /// # See Also
     /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
     pub async fn get_host_status(&mut self, id: &str) -> Result<Response<Host>, Error> {
-        let method = "web.get_host_status";
-        let data = json!({
-            "method": method,
-            "params": [ id ],
-            "id": get_random_u32()
-        });
-        let response = self.request(method, data).await?;
-        deserialize_response(method, response).await
+        self.call("web.get_host_status", (id,)).await
     }
 }
 
@@ -82,17 +75,15 @@ impl Serialize for Status {
 
 #[cfg(test)]
 mod tests {
-    use crate::{DelugeClient, DelugeClientOptions};
+    use crate::testing::MockServer;
     use log::trace;
-    use rogue_config::{OptionsProvider, YamlOptionsProvider};
-    use rogue_logging::{Error, LoggerBuilder};
+    use rogue_logging::Error;
 
     #[tokio::test]
     async fn get_host_status() -> Result<(), Error> {
         // Arrange
-        let _ = LoggerBuilder::new().create();
-        let options: DelugeClientOptions = YamlOptionsProvider::get()?;
-        let mut client = DelugeClient::from_options(options);
+        let server = MockServer::start().await?;
+        let mut client = server.client();
 
         // Act
         let response = client.login().await?;
@@ -1,22 +1,15 @@
-use crate::client::{deserialize_response, get_random_u32};
 use crate::{DelugeClient, Response};
 use rogue_logging::Error;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 impl DelugeClient {
     /// Get the hosts in the hostlist.
     ///
+    /// Only available with the web transport as the daemon has no equivalent.
+    ///
     /// # See Also
     /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
     pub async fn get_hosts(&mut self) -> Result<Response<Vec<Host>>, Error> {
-        let method = "web.get_hosts";
-        let data = json!({
-            "method": method,
-            "params": [],
-            "id": get_random_u32()
-        });
-        let response = self.request(method, data).await?;
-        deserialize_response(method, response).await
+        self.call("web.get_hosts", ()).await
     }
 }
 #[derive(Debug, Serialize, Deserialize)]
@@ -30,17 +23,15 @@ pub struct Host {
 
 #[cfg(test)]
 mod tests {
-    use crate::{DelugeClient, DelugeClientOptions};
+    use crate::testing::MockServer;
     use log::trace;
-    use rogue_config::{OptionsProvider, YamlOptionsProvider};
-    use rogue_logging::{Error, LoggerBuilder};
+    use rogue_logging::Error;
 
     #[tokio::test]
     async fn get_hosts() -> Result<(), Error> {
         // Arrange
-        let _ = LoggerBuilder::new().create();
-        let options: DelugeClientOptions = YamlOptionsProvider::get()?;
-        let mut client = DelugeClient::from_options(options);
+        let server = MockServer::start().await?;
+        let mut client = server.client();
 
         // Act
         let response = client.login().await?;
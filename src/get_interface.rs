@@ -1,10 +1,9 @@
-use crate::client::{deserialize_response, get_random_u32};
+use crate::filter_tree::{FilterCategory, FilterCounts};
 use crate::get_torrents::{FilterOptions, Torrent};
 use crate::{DelugeClient, Response};
 use rogue_logging::Error;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap};
 
 impl DelugeClient {
     /// Gather the information required for updating the web interface.
@@ -15,15 +14,8 @@ impl DelugeClient {
         &mut self,
         filters: FilterOptions,
     ) -> Result<Response<Interface>, Error> {
-        let method = "web.update_ui";
-        let data = json!({
-            "method": method,
-            "params": [ [], filters ],
-            "id": get_random_u32()
-        });
-        println!("{data}");
-        let response = self.request(method, data).await?;
-        deserialize_response(method, response).await
+        let keys: Vec<String> = Vec::new();
+        self.call("web.update_ui", (keys, filters)).await
     }
 }
 
@@ -35,12 +27,38 @@ pub struct Interface {
     pub torrents: Option<HashMap<String, Torrent>>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Number of torrents for each value of each filter category
+///
+/// Every category is optional as some are only present when a plugin is
+/// enabled, for example `label` requires the Label plugin.
+/// Categories that are not modelled are kept in `other`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Counts {
-    pub label: HashSet<(String, u64)>,
-    pub owner: HashSet<(String, u64)>,
-    pub state: HashSet<(String, u64)>,
-    pub tracker_host: HashSet<(String, u64)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<FilterCounts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<FilterCounts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<FilterCounts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracker_host: Option<FilterCounts>,
+    /// Categories defined by plugins ordered by name
+    #[serde(flatten)]
+    pub other: BTreeMap<String, FilterCounts>,
+}
+
+impl Counts {
+    /// Get the counts for a category
+    #[must_use]
+    pub fn get(&self, category: &FilterCategory) -> Option<&FilterCounts> {
+        match category {
+            FilterCategory::Label => self.label.as_ref(),
+            FilterCategory::Owner => self.owner.as_ref(),
+            FilterCategory::State => self.state.as_ref(),
+            FilterCategory::TrackerHost => self.tracker_host.as_ref(),
+            FilterCategory::Other(name) => self.other.get(name),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -61,24 +79,33 @@ pub struct Stats {
 
 #[cfg(test)]
 mod tests {
-
+    use super::*;
     use crate::get_torrents::FilterOptions;
-    use crate::DelugeClient;
-    use crate::DelugeClientOptions;
+    use crate::testing::{MockServer, MockTorrent};
     use log::trace;
-    use rogue_config::{OptionsProvider, YamlOptionsProvider};
-    use rogue_logging::{Error, LoggerBuilder};
+    use rogue_logging::Error;
+    use serde_json::json;
 
     #[tokio::test]
     async fn get_interface() -> Result<(), Error> {
         // Arrange
-        let _ = LoggerBuilder::new().create();
-        let options: DelugeClientOptions = YamlOptionsProvider::get()?;
-        let mut client = DelugeClient::from_options(options.clone());
+        let server = MockServer::start().await?;
+        server.state().torrents.insert(
+            "a".repeat(40),
+            MockTorrent {
+                label: "linux".to_owned(),
+                ..MockTorrent::default()
+            },
+        );
+        server
+            .state()
+            .torrents
+            .insert("b".repeat(40), MockTorrent::default());
         let filters = FilterOptions {
             label: Some(vec!["linux".to_owned()]),
             ..FilterOptions::default()
         };
+        let mut client = server.client();
 
         // Act
         let response = client.login().await?;
@@ -88,7 +115,37 @@ mod tests {
 
         // Assert
         let result = response.get_result("get_interface")?;
-        assert!(result.torrents.is_some());
+        assert_eq!(result.torrents.map(|x| x.len()), Some(1));
         Ok(())
     }
+
+    #[test]
+    fn deserialize_counts() {
+        // Arrange
+        let json = json!({
+            "owner": [["All", 3], ["admin", 3]],
+            "state": [["All", 3], ["Seeding", 2], ["Paused", 1]],
+            "tracker_host": [["All", 3], ["example.com", 3]],
+            "source": [["All", 3], ["example", 1]],
+            "category": [["All", 3]]
+        });
+
+        // Act
+        let counts: Counts = serde_json::from_value(json).expect("should deserialize");
+
+        // Assert
+        assert!(counts.label.is_none());
+        let state = counts
+            .get(&FilterCategory::State)
+            .expect("should have state");
+        let values: Vec<&str> = state.iter().map(|x| x.value.as_str()).collect();
+        assert_eq!(values, vec!["All", "Seeding", "Paused"]);
+        assert_eq!(state.get("Seeding"), Some(2));
+        let source = counts
+            .get(&FilterCategory::Other("source".to_owned()))
+            .expect("should have source");
+        assert_eq!(source.get("example"), Some(1));
+        let other: Vec<&str> = counts.other.keys().map(String::as_str).collect();
+        assert_eq!(other, vec!["category", "source"]);
+    }
 }
@@ -1,9 +1,7 @@
-use crate::client::{deserialize_response, get_random_u32};
 use crate::State;
 use crate::{DelugeClient, Response};
 use rogue_logging::Error;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 
 impl DelugeClient {
     /// Get the status for a torrent, filtered by status keys.
@@ -11,14 +9,8 @@ impl DelugeClient {
     /// # See Also
     /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
     pub async fn get_torrent_status(&mut self, id: &str) -> Result<Response<Torrent>, Error> {
-        let method = "web.get_torrent_status";
-        let data = json!({
-            "method": method,
-            "params": [ id, [] ],
-            "id": get_random_u32()
-        });
-        let response = self.request(method, data).await?;
-        deserialize_response(method, response).await
+        let keys: Vec<String> = Vec::new();
+        self.call("web.get_torrent_status", (id, keys)).await
     }
 }
 #[derive(Debug, Deserialize, Serialize)]
@@ -55,35 +47,31 @@ pub struct Torrent {
 
 #[cfg(test)]
 mod tests {
-    use crate::DelugeClient;
-    use crate::DelugeClientOptions;
+    use crate::testing::MockServer;
+    use crate::testing::MockTorrent;
     use log::trace;
-    use rogue_config::{OptionsProvider, YamlOptionsProvider};
-    use rogue_logging::{Error, LoggerBuilder};
-    use serde::Deserialize;
-
-    #[derive(Debug, Deserialize)]
-    struct ExampleValues {
-        torrent_id: String,
-    }
+    use rogue_logging::Error;
 
     #[tokio::test]
     async fn get_torrent_status() -> Result<(), Error> {
         // Arrange
-        let _ = LoggerBuilder::new().create();
-        let options: DelugeClientOptions = YamlOptionsProvider::get()?;
-        let mut client = DelugeClient::from_options(options.clone());
-        let options: ExampleValues = YamlOptionsProvider::get()?;
+        let server = MockServer::start().await?;
+        let id = "a".repeat(40);
+        server
+            .state()
+            .torrents
+            .insert(id.clone(), MockTorrent::default());
+        let mut client = server.client();
 
         // Act
         let response = client.login().await?;
         trace!("{}", response.to_json_pretty());
-        let id = options.torrent_id;
         let response = client.get_torrent_status(&id).await?;
         trace!("{}", response.to_json_pretty());
 
         // Assert
-        let _result = response.get_result("get_torrent_status")?;
+        let result = response.get_result("get_torrent_status")?;
+        assert_eq!(result.name, "example");
         Ok(())
     }
 }
@@ -1,10 +1,8 @@
-use crate::client::{deserialize_response, get_random_u32};
 use crate::get_interface::Interface;
 use crate::State;
 use crate::{DelugeClient, Response};
 use rogue_logging::Error;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use std::collections::HashMap;
 
 impl DelugeClient {
@@ -15,14 +13,8 @@ impl DelugeClient {
         &mut self,
         filters: FilterOptions,
     ) -> Result<Response<HashMap<String, Torrent>>, Error> {
-        let method = "web.update_ui";
-        let data = json!({
-            "method": method,
-            "params": [ [], filters ],
-            "id": get_random_u32()
-        });
-        let response = self.request(method, data).await?;
-        let response = deserialize_response::<Interface>(method, response).await?;
+        let keys: Vec<String> = Vec::new();
+        let response: Response<Interface> = self.call("web.update_ui", (keys, filters)).await?;
         Ok(Response {
             status_code: response.status_code,
             result: response.result.map(|x| x.torrents.unwrap_or_default()),
@@ -32,7 +24,7 @@ impl DelugeClient {
     }
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct FilterOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub state: Option<Vec<String>>,
@@ -42,37 +34,64 @@ pub struct FilterOptions {
     pub label: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Vec<String>>,
+    /// Filters defined by plugins keyed by filter name
+    #[serde(flatten)]
+    pub other: HashMap<String, Vec<String>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Torrent {
+    /// Unix timestamp the download completed, `0` if not completed
+    #[serde(default)]
+    pub completed_time: u64,
     pub label: String,
     pub name: String,
     pub progress: f64,
+    #[serde(default)]
+    pub ratio: f64,
     pub save_path: String,
+    /// Seconds spent seeding
+    #[serde(default)]
+    pub seeding_time: u64,
     pub state: State,
     pub total_remaining: u64,
+    #[serde(default)]
+    pub total_size: u64,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::DelugeClient;
-    use crate::DelugeClientOptions;
+    use crate::testing::MockServer;
+    use crate::testing::MockTorrent;
     use log::trace;
-    use rogue_config::{OptionsProvider, YamlOptionsProvider};
-    use rogue_logging::{Error, LoggerBuilder};
+    use rogue_logging::Error;
+    use serde_json::json;
 
     #[tokio::test]
     async fn get_torrents() -> Result<(), Error> {
         // Arrange
-        let _ = LoggerBuilder::new().create();
-        let options: DelugeClientOptions = YamlOptionsProvider::get()?;
-        let mut client = DelugeClient::from_options(options.clone());
+        let server = MockServer::start().await?;
+        server.state().torrents.insert(
+            "a".repeat(40),
+            MockTorrent {
+                label: "linux".to_owned(),
+                ..MockTorrent::default()
+            },
+        );
+        server
+            .state()
+            .torrents
+            .insert("b".repeat(40), MockTorrent::default());
         let filters = FilterOptions {
             label: Some(vec!["linux".to_owned()]),
             ..FilterOptions::default()
         };
+        let mut client = server.client();
 
         // Act
         let response = client.login().await?;
@@ -82,7 +101,27 @@ mod tests {
 
         // Assert
         let result = response.get_result("get_torrents")?;
-        assert!(!result.is_empty());
+        assert_eq!(result.len(), 1);
         Ok(())
     }
+
+    #[test]
+    fn deserialize_torrent_without_optional_fields() {
+        // Arrange
+        let json = json!({
+            "label": "linux",
+            "name": "example",
+            "progress": 100.0,
+            "save_path": "/downloads",
+            "state": "Seeding",
+            "total_remaining": 0
+        });
+
+        // Act
+        let torrent: Torrent = serde_json::from_value(json).expect("should deserialize");
+
+        // Assert
+        assert_eq!(torrent.total_size, 0);
+        assert_eq!(torrent.completed_time, 0);
+    }
 }
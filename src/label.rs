@@ -0,0 +1,185 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+impl DelugeClient {
+    /// Get the labels.
+    ///
+    /// Requires the Label plugin to be enabled.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_labels(&mut self) -> Result<Response<Vec<String>>, Error> {
+        self.call("label.get_labels", ()).await
+    }
+
+    /// Add a label.
+    ///
+    /// Labels must be lowercase alphanumeric with `-` or `_`.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn add_label(&mut self, label: &str) -> Result<Response<()>, Error> {
+        self.call("label.add", (label,)).await
+    }
+
+    /// Remove a label.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn remove_label(&mut self, label: &str) -> Result<Response<()>, Error> {
+        self.call("label.remove", (label,)).await
+    }
+
+    /// Set the label of a torrent.
+    ///
+    /// An empty label removes the label from the torrent.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn set_torrent_label(
+        &mut self,
+        torrent_id: &str,
+        label: &str,
+    ) -> Result<Response<()>, Error> {
+        self.call("label.set_torrent", (torrent_id, label)).await
+    }
+
+    /// Get the options of a label.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_label_options(
+        &mut self,
+        label: &str,
+    ) -> Result<Response<LabelOptions>, Error> {
+        self.call("label.get_options", (label,)).await
+    }
+
+    /// Set the options of a label.
+    ///
+    /// Only the fields that are set are sent so partial options can be applied.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn set_label_options(
+        &mut self,
+        label: &str,
+        options: LabelOptions,
+    ) -> Result<Response<()>, Error> {
+        self.call("label.set_options", (label, options)).await
+    }
+
+    /// Get the Label plugin configuration.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_label_config(&mut self) -> Result<Response<HashMap<String, Value>>, Error> {
+        self.call("label.get_config", ()).await
+    }
+}
+
+/// Options of a label
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LabelOptions {
+    /// Apply the bandwidth limits to torrents with this label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apply_max: Option<bool>,
+    /// Maximum download speed in KiB/s, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_download_speed: Option<f64>,
+    /// Maximum upload speed in KiB/s, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_upload_speed: Option<f64>,
+    /// Maximum number of connections, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_connections: Option<i64>,
+    /// Maximum number of upload slots, `-1` for unlimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_upload_slots: Option<i64>,
+    /// Prioritize the first and last pieces
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prioritize_first_last: Option<bool>,
+
+    /// Apply the queue settings to torrents with this label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apply_queue: Option<bool>,
+    /// Automatically manage the torrent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_auto_managed: Option<bool>,
+    /// Stop seeding when the share ratio reaches `stop_ratio`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_at_ratio: Option<bool>,
+    /// Share ratio at which to stop seeding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_ratio: Option<f64>,
+    /// Remove the torrent when it stops seeding at the ratio
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove_at_ratio: Option<bool>,
+
+    /// Apply the move completed settings to torrents with this label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apply_move_completed: Option<bool>,
+    /// Move completed downloads to another directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_completed: Option<bool>,
+    /// Path to move completed downloads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_completed_path: Option<String>,
+
+    /// Automatically apply this label to torrents from `auto_add_trackers`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_add: Option<bool>,
+    /// Tracker hosts to automatically apply this label to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_add_trackers: Option<Vec<String>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::MockServer;
+    use log::trace;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn get_labels() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.add_label("linux").await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_labels().await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_labels")?;
+        assert!(!result.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_label_options() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.add_label("linux").await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_label_options("linux").await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_label_options")?;
+        assert!(result.apply_max.is_some());
+        Ok(())
+    }
+}
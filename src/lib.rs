@@ -1,18 +1,55 @@
+pub use batch::*;
+pub use call::*;
 pub use client::*;
+pub use daemon::{DaemonCertificate, DaemonTransport, DEFAULT_DAEMON_PORT};
 pub use factory::*;
 pub use options::*;
+pub use recording::{Exchange, RecordingTransport, ReplayTransport};
 pub use response::*;
 pub use state::*;
+pub use transport::*;
+pub use web::WebTransport;
 
 pub mod add_torrents;
+pub mod autoadd;
+mod batch;
+mod bencode;
+pub mod blocklist;
+mod call;
 mod client;
+pub mod connection;
+pub mod core_config;
+pub mod create_torrent;
+mod daemon;
+mod daemon_web;
+pub mod disk_space;
+pub mod events;
+pub mod execute;
+pub mod extractor;
 mod factory;
+pub mod filter_tree;
 pub mod get_host_status;
 pub mod get_hosts;
 pub mod get_interface;
 pub mod get_torrent_status;
 pub mod get_torrents;
+pub mod label;
 pub mod login;
+pub mod notifications;
 mod options;
+pub mod plugins;
+pub mod query;
+mod recording;
+mod rencode;
 mod response;
+pub mod scheduler;
+pub mod session_status;
 mod state;
+pub mod stats;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod torrent_actions;
+mod transport;
+pub mod watcher;
+mod web;
+pub mod web_config;
@@ -0,0 +1,104 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+impl DelugeClient {
+    /// Get the Notifications plugin configuration.
+    ///
+    /// Requires the Notifications plugin to be enabled.
+    pub async fn get_notifications_config(
+        &mut self,
+    ) -> Result<Response<NotificationsConfig>, Error> {
+        self.call("notifications.get_config", ()).await
+    }
+
+    /// Set the Notifications plugin configuration.
+    ///
+    /// Only the fields that are set are sent so a partial config can be applied.
+    pub async fn set_notifications_config(
+        &mut self,
+        config: NotificationsConfig,
+    ) -> Result<Response<()>, Error> {
+        self.call("notifications.set_config", (config,)).await
+    }
+}
+
+/// Notifications plugin configuration
+///
+/// Keys that are not modelled are kept in `other`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct NotificationsConfig {
+    /// Send email notifications
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smtp_enabled: Option<bool>,
+    /// SMTP server host
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smtp_host: Option<String>,
+    /// SMTP server port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smtp_port: Option<u16>,
+    /// SMTP username
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smtp_user: Option<String>,
+    /// SMTP password
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smtp_pass: Option<String>,
+    /// Sender address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smtp_from: Option<String>,
+    /// Connect to the SMTP server with TLS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smtp_tls: Option<bool>,
+    /// Recipient addresses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smtp_recipients: Option<Vec<String>>,
+    /// Events that trigger a notification
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscriptions: Option<Subscriptions>,
+
+    /// Configuration values that are not modelled
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+/// Names of the events that trigger a notification for each notification type
+///
+/// # Examples
+/// - `TorrentFinishedEvent`
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Subscriptions {
+    /// Events that trigger an email
+    #[serde(default)]
+    pub email: Vec<String>,
+
+    /// Notification types that are not modelled
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::MockServer;
+    use log::trace;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn get_notifications_config() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_notifications_config().await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_notifications_config")?;
+        assert!(result.smtp_enabled.is_some());
+        Ok(())
+    }
+}
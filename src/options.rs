@@ -4,6 +4,9 @@ use serde::{Deserialize, Serialize};
 pub struct DelugeClientOptions {
     /// Deluge Web API host including port but without protocol or password
     ///
+    /// With [`TransportKind::Daemon`] this is the daemon host and the port
+    /// defaults to [`DEFAULT_DAEMON_PORT`](crate::DEFAULT_DAEMON_PORT).
+    ///
     /// # Examples
     /// - `localhost`
     /// - `example.com`
@@ -14,6 +17,28 @@ pub struct DelugeClientOptions {
     /// Deluge Web API password
     pub password: String,
 
+    /// Transport used to connect to Deluge
+    ///
+    /// Defaults to [`TransportKind::Web`].
+    pub transport: Option<TransportKind>,
+
+    /// Deluge daemon username
+    ///
+    /// Only used by [`TransportKind::Daemon`].
+    pub username: Option<String>,
+
+    /// Path of the PEM certificate of the daemon to pin
+    ///
+    /// Only used by [`TransportKind::Daemon`]. The daemon's certificate is
+    /// `ssl/daemon.cert` in its config directory.
+    pub daemon_certificate: Option<String>,
+
+    /// Accept any daemon certificate if `daemon_certificate` is not set
+    ///
+    /// Only used by [`TransportKind::Daemon`]. The connection is encrypted but
+    /// the daemon is not authenticated.
+    pub accept_any_daemon_certificate: Option<bool>,
+
     /// User agent
     pub user_agent: Option<String>,
 
@@ -22,4 +47,24 @@ pub struct DelugeClientOptions {
 
     /// Duration before rate limit is reset
     pub rate_limit_duration: Option<usize>,
+
+    /// Refuse to add torrents that do not fit in the free space of their
+    /// download location
+    ///
+    /// See [`DelugeClient::check_free_space`](crate::DelugeClient::check_free_space).
+    pub free_space_guard: Option<bool>,
+}
+
+/// How a [`DelugeClient`](crate::DelugeClient) connects to Deluge
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportKind {
+    /// JSON-RPC over HTTP to the `/json` endpoint of deluge-web
+    #[default]
+    Web,
+    /// Native RPC over TLS to the daemon
+    ///
+    /// Methods in the `web` namespace are not available except for the event
+    /// methods and `auth.login`, which are translated to daemon methods.
+    Daemon,
 }
@@ -0,0 +1,155 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::fs::read;
+
+impl DelugeClient {
+    /// Get the names of the plugins available to the daemon.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_available_plugins(&mut self) -> Result<Response<Vec<String>>, Error> {
+        self.call("core.get_available_plugins", ()).await
+    }
+
+    /// Get the names of the plugins enabled on the daemon.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_enabled_plugins(&mut self) -> Result<Response<Vec<String>>, Error> {
+        self.call("core.get_enabled_plugins", ()).await
+    }
+
+    /// Enable a plugin on the daemon.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn enable_plugin(&mut self, name: &str) -> Result<Response<bool>, Error> {
+        self.call("core.enable_plugin", (name,)).await
+    }
+
+    /// Disable a plugin on the daemon.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn disable_plugin(&mut self, name: &str) -> Result<Response<bool>, Error> {
+        self.call("core.disable_plugin", (name,)).await
+    }
+
+    /// Rescan the plugin folders for new plugins.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn rescan_plugins(&mut self) -> Result<Response<()>, Error> {
+        self.call("core.rescan_plugins", ()).await
+    }
+
+    /// Get the enabled and available plugins of the web interface.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn get_plugins(&mut self) -> Result<Response<Plugins>, Error> {
+        self.call("web.get_plugins", ()).await
+    }
+
+    /// Get the metadata of a plugin.
+    ///
+    /// Only available with the web transport as the daemon has no equivalent.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn get_plugin_info(&mut self, name: &str) -> Result<Response<PluginInfo>, Error> {
+        self.call("web.get_plugin_info", (name,)).await
+    }
+
+    /// Upload and install a plugin `.egg` file.
+    ///
+    /// The file is first uploaded to the web server then copied into the plugins
+    /// folder and the plugins are rescanned. The plugin still has to be enabled.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn upload_plugin(&mut self, path: &Path) -> Result<Response<bool>, Error> {
+        let file_name = path
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .ok_or_else(|| Error {
+                action: "get file name of plugin".to_owned(),
+                message: format!("Path has no file name: {}", path.display()),
+                ..Error::default()
+            })?;
+        let bytes = read(path).await.map_err(|e| Error {
+            action: "read plugin file".to_owned(),
+            domain: Some("file system".to_owned()),
+            message: e.to_string(),
+            ..Error::default()
+        })?;
+        let upload_path = self.transport.upload(file_name.clone(), bytes).await?;
+        self.call("web.upload_plugin", (file_name, upload_path))
+            .await
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Plugins {
+    pub enabled_plugins: Vec<String>,
+    pub available_plugins: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PluginInfo {
+    pub name: String,
+    pub version: String,
+    pub author: String,
+    #[serde(rename = "Author-email")]
+    pub author_email: String,
+    #[serde(rename = "Home-page")]
+    pub home_page: String,
+    pub license: String,
+    pub description: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::MockServer;
+    use log::trace;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn get_plugins() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_plugins().await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_plugins")?;
+        assert!(!result.available_plugins.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_enabled_plugins() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_enabled_plugins().await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_enabled_plugins")?;
+        assert!(result.contains(&"Label".to_owned()));
+        Ok(())
+    }
+}
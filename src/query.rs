@@ -0,0 +1,239 @@
+use crate::get_torrents::{FilterOptions, Torrent};
+use crate::{DelugeClient, Response, State};
+use rogue_logging::Error;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+impl DelugeClient {
+    /// Get all torrents matching a query
+    ///
+    /// The server side filters are applied by Deluge then the client side
+    /// predicates are applied to the result.
+    pub async fn query_torrents(
+        &mut self,
+        query: TorrentQuery,
+    ) -> Result<Response<HashMap<String, Torrent>>, Error> {
+        let mut response = self.get_torrents(query.filters.clone()).await?;
+        if let Some(torrents) = response.result.as_mut() {
+            torrents.retain(|_, torrent| query.matches(torrent));
+        }
+        Ok(response)
+    }
+}
+
+/// Combine server side [`FilterOptions`] with client side predicates
+///
+/// # Examples
+/// ```
+/// use deluge_api::query::TorrentQuery;
+/// use deluge_api::State;
+/// use std::time::Duration;
+///
+/// let query = TorrentQuery::new()
+///     .state(State::Seeding)
+///     .label("linux")
+///     .ratio_above(2.0)
+///     .seeding_time_above(Duration::from_secs(7 * 24 * 60 * 60));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TorrentQuery {
+    /// Filters applied by Deluge
+    pub filters: FilterOptions,
+    /// Predicates applied to the torrents returned by Deluge
+    pub predicates: Vec<Predicate>,
+}
+
+/// A client side condition a torrent must meet
+#[derive(Clone, Debug)]
+pub enum Predicate {
+    /// Share ratio is greater than the value
+    RatioAbove(f64),
+    /// Seeding time is greater than the duration
+    SeedingTimeAbove(Duration),
+    /// Total size in bytes is within the inclusive range
+    SizeBetween(u64, u64),
+    /// Download completed before the time
+    CompletedBefore(SystemTime),
+}
+
+impl Predicate {
+    /// Does the torrent meet the condition
+    #[must_use]
+    pub fn matches(&self, torrent: &Torrent) -> bool {
+        match self {
+            Predicate::RatioAbove(ratio) => torrent.ratio > *ratio,
+            Predicate::SeedingTimeAbove(duration) => torrent.seeding_time > duration.as_secs(),
+            Predicate::SizeBetween(min, max) => {
+                torrent.total_size >= *min && torrent.total_size <= *max
+            }
+            Predicate::CompletedBefore(time) => {
+                let timestamp = time
+                    .duration_since(UNIX_EPOCH)
+                    .map(|x| x.as_secs())
+                    .unwrap_or_default();
+                torrent.completed_time > 0 && torrent.completed_time < timestamp
+            }
+        }
+    }
+}
+
+impl TorrentQuery {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only include torrents in a state
+    ///
+    /// May be called multiple times to include several states.
+    #[must_use]
+    pub fn state(mut self, state: State) -> Self {
+        if let Ok(Value::String(state)) = serde_json::to_value(state) {
+            self.filters.state.get_or_insert_with(Vec::new).push(state);
+        }
+        self
+    }
+
+    /// Only include torrents with a label
+    #[must_use]
+    pub fn label(mut self, label: &str) -> Self {
+        push(&mut self.filters.label, label);
+        self
+    }
+
+    /// Only include torrents from a tracker host
+    #[must_use]
+    pub fn tracker_host(mut self, tracker_host: &str) -> Self {
+        push(&mut self.filters.tracker_host, tracker_host);
+        self
+    }
+
+    /// Only include torrents with a name
+    #[must_use]
+    pub fn name(mut self, name: &str) -> Self {
+        push(&mut self.filters.name, name);
+        self
+    }
+
+    /// Only include torrents owned by a user
+    #[must_use]
+    pub fn owner(mut self, owner: &str) -> Self {
+        push(&mut self.filters.owner, owner);
+        self
+    }
+
+    /// Only include a torrent by id
+    #[must_use]
+    pub fn id(mut self, id: &str) -> Self {
+        push(&mut self.filters.id, id);
+        self
+    }
+
+    /// Only include torrents matching a filter defined by a plugin
+    #[must_use]
+    pub fn filter(mut self, key: &str, value: &str) -> Self {
+        self.filters
+            .other
+            .entry(key.to_owned())
+            .or_default()
+            .push(value.to_owned());
+        self
+    }
+
+    /// Only include torrents with a share ratio greater than `ratio`
+    #[must_use]
+    pub fn ratio_above(self, ratio: f64) -> Self {
+        self.predicate(Predicate::RatioAbove(ratio))
+    }
+
+    /// Only include torrents that have seeded for longer than `duration`
+    #[must_use]
+    pub fn seeding_time_above(self, duration: Duration) -> Self {
+        self.predicate(Predicate::SeedingTimeAbove(duration))
+    }
+
+    /// Only include torrents with a total size in bytes from `min` to `max` inclusive
+    #[must_use]
+    pub fn size_between(self, min: u64, max: u64) -> Self {
+        self.predicate(Predicate::SizeBetween(min, max))
+    }
+
+    /// Only include torrents that completed downloading before `time`
+    #[must_use]
+    pub fn completed_before(self, time: SystemTime) -> Self {
+        self.predicate(Predicate::CompletedBefore(time))
+    }
+
+    /// Only include torrents meeting a client side predicate
+    #[must_use]
+    pub fn predicate(mut self, predicate: Predicate) -> Self {
+        self.predicates.push(predicate);
+        self
+    }
+
+    /// Does the torrent meet every client side predicate
+    ///
+    /// Server side filters are not checked.
+    #[must_use]
+    pub fn matches(&self, torrent: &Torrent) -> bool {
+        self.predicates.iter().all(|x| x.matches(torrent))
+    }
+}
+
+fn push(values: &mut Option<Vec<String>>, value: &str) {
+    values.get_or_insert_with(Vec::new).push(value.to_owned());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn torrent(ratio: f64, seeding_time: u64, total_size: u64, completed_time: u64) -> Torrent {
+        Torrent {
+            completed_time,
+            label: "linux".to_owned(),
+            name: "example".to_owned(),
+            progress: 100.0,
+            ratio,
+            save_path: "/srv/shared/tests".to_owned(),
+            seeding_time,
+            state: State::Seeding,
+            total_remaining: 0,
+            total_size,
+        }
+    }
+
+    #[test]
+    fn torrent_query() {
+        // Arrange
+        let query = TorrentQuery::new()
+            .state(State::Seeding)
+            .label("linux")
+            .filter("source", "example")
+            .ratio_above(1.0)
+            .seeding_time_above(Duration::from_secs(90))
+            .size_between(100, 1000)
+            .completed_before(UNIX_EPOCH + Duration::from_secs(2000));
+
+        // Act
+        let filters = serde_json::to_value(&query.filters).expect("should serialize");
+
+        // Assert
+        assert_eq!(
+            filters,
+            json!({
+                "state": ["Seeding"],
+                "label": ["linux"],
+                "source": ["example"]
+            })
+        );
+        assert!(query.matches(&torrent(2.0, 120, 500, 1000)));
+        assert!(!query.matches(&torrent(0.5, 120, 500, 1000)));
+        assert!(!query.matches(&torrent(2.0, 30, 500, 1000)));
+        assert!(!query.matches(&torrent(2.0, 120, 5000, 1000)));
+        assert!(!query.matches(&torrent(2.0, 120, 500, 3000)));
+        assert!(!query.matches(&torrent(2.0, 120, 500, 0)));
+    }
+}
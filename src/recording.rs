@@ -0,0 +1,476 @@
+use crate::{DelugeClient, RawResponse, Response, Transport, TransportRequest};
+use colored::Colorize;
+use futures::future::BoxFuture;
+use log::*;
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::fs::{self, read_to_string};
+use std::path::Path;
+use std::time::SystemTime;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+
+/// Replaces the params of [`REDACTED_METHODS`] so secrets are never written
+const REDACTED: &str = "<redacted>";
+
+/// Methods with a password or config values in their params
+///
+/// The params of every `*.set_config` method are also redacted as they may
+/// include passwords, for example of a proxy or an SMTP server.
+const REDACTED_METHODS: [&str; 5] = [
+    "auth.login",
+    "auth.change_password",
+    "daemon.login",
+    "web.add_host",
+    "web.edit_host",
+];
+
+/// Method recorded for [`Transport::upload`]
+const UPLOAD: &str = "upload";
+
+/// A request and its response captured by [`RecordingTransport`]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Exchange {
+    /// Id of the request
+    pub id: u32,
+    /// Method of the request
+    ///
+    /// Uploads are recorded as `upload` with the file name as the only param.
+    pub method: String,
+    /// Params of the request
+    ///
+    /// Passwords and config values are redacted.
+    pub params: Value,
+    /// HTTP status code of the response
+    pub status_code: Option<u16>,
+    /// Body of the response as received
+    ///
+    /// Responses of [`DaemonTransport`](crate::DaemonTransport) are not JSON
+    /// so they are recorded as the equivalent JSON-RPC response. Uploads are
+    /// recorded as a JSON-RPC response with the path as the result.
+    pub body: Option<String>,
+    /// Message of the [`Error`] returned by the transport if the request failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+    /// Seconds until the response was received
+    pub elapsed: f64,
+}
+
+impl DelugeClient {
+    /// Record every request and response to a JSONL file.
+    ///
+    /// Each line is an [`Exchange`]. Passwords and config values sent by the
+    /// client are redacted but responses are recorded as received, so a
+    /// recording of [`DelugeClient::get_config`] includes the proxy password.
+    ///
+    /// Serve the recording back with [`ReplayTransport`].
+    pub fn with_recording(self, path: &Path) -> Result<Self, Error> {
+        let transport = RecordingTransport::create(self.transport, path)?;
+        Ok(Self {
+            transport: Box::new(transport),
+            ..self
+        })
+    }
+}
+
+/// Wrap a [`Transport`] to record every request and response to a JSONL file
+///
+/// Created by [`DelugeClient::with_recording`].
+pub struct RecordingTransport {
+    inner: Box<dyn Transport>,
+    file: File,
+}
+
+impl RecordingTransport {
+    /// Create or truncate the file and record the calls sent by `inner`
+    pub fn create(inner: Box<dyn Transport>, path: &Path) -> Result<Self, Error> {
+        let file = fs::File::create(path).map_err(|e| Error {
+            action: "create recording".to_owned(),
+            domain: Some("file system".to_owned()),
+            message: format!("{}\n{e}", path.display()),
+            ..Error::default()
+        })?;
+        Ok(Self {
+            inner,
+            file: File::from_std(file),
+        })
+    }
+
+    async fn record(
+        &mut self,
+        id: u32,
+        method: &str,
+        params: Value,
+        response: &Result<RawResponse, Error>,
+        start: SystemTime,
+    ) {
+        let elapsed = start
+            .elapsed()
+            .expect("elapsed should not fail")
+            .as_secs_f64();
+        let params = if is_redacted(method) {
+            json!([REDACTED])
+        } else {
+            params
+        };
+        let exchange = match response {
+            Ok(response) => Exchange {
+                id,
+                method: method.to_owned(),
+                params,
+                status_code: response.status_code,
+                body: Some(response.body.clone()),
+                failure: None,
+                elapsed,
+            },
+            Err(error) => Exchange {
+                id,
+                method: method.to_owned(),
+                params,
+                status_code: error.status_code,
+                body: None,
+                failure: Some(error.message.clone()),
+                elapsed,
+            },
+        };
+        let mut line = serde_json::to_string(&exchange).expect("exchange should serialize");
+        line.push('\n');
+        let result = match self.file.write_all(line.as_bytes()).await {
+            Ok(()) => self.file.flush().await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            warn!("{} to record {method} #{id}: {e}", "Failed".bold());
+        }
+    }
+}
+
+fn is_redacted(method: &str) -> bool {
+    REDACTED_METHODS.contains(&method) || method.ends_with(".set_config")
+}
+
+impl Transport for RecordingTransport {
+    fn send<'a>(
+        &'a mut self,
+        id: u32,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<Response<Value>, Error>> {
+        Box::pin(async move { self.send_raw(id, method, params).await?.parse(method) })
+    }
+
+    fn send_raw<'a>(
+        &'a mut self,
+        id: u32,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<RawResponse, Error>> {
+        Box::pin(async move {
+            let start = SystemTime::now();
+            let response = self.inner.send_raw(id, method, params.clone()).await;
+            self.record(id, method, params, &response, start).await;
+            response
+        })
+    }
+
+    fn send_batch(
+        &mut self,
+        requests: Vec<TransportRequest>,
+    ) -> BoxFuture<'_, Vec<Result<Response<Value>, Error>>> {
+        Box::pin(async move {
+            let methods: Vec<String> = requests.iter().map(|x| x.method.clone()).collect();
+            let responses = self.send_batch_raw(requests).await;
+            methods
+                .iter()
+                .zip(responses)
+                .map(|(method, response)| response?.parse(method))
+                .collect()
+        })
+    }
+
+    fn send_batch_raw(
+        &mut self,
+        requests: Vec<TransportRequest>,
+    ) -> BoxFuture<'_, Vec<Result<RawResponse, Error>>> {
+        Box::pin(async move {
+            let start = SystemTime::now();
+            let responses = self.inner.send_batch_raw(requests.clone()).await;
+            for (request, response) in requests.into_iter().zip(responses.iter()) {
+                self.record(request.id, &request.method, request.params, response, start)
+                    .await;
+            }
+            responses
+        })
+    }
+
+    fn upload(
+        &mut self,
+        file_name: String,
+        bytes: Vec<u8>,
+    ) -> BoxFuture<'_, Result<String, Error>> {
+        Box::pin(async move {
+            let start = SystemTime::now();
+            let result = self.inner.upload(file_name.clone(), bytes).await;
+            let response = result.as_ref().map(|path| {
+                RawResponse::from_response(&Response {
+                    status_code: Some(200),
+                    result: Some(Value::String(path.clone())),
+                    error: None,
+                    id: None,
+                })
+            });
+            let response = response.map_err(Clone::clone);
+            self.record(0, UPLOAD, json!([file_name]), &response, start)
+                .await;
+            result
+        })
+    }
+}
+
+/// Serve the responses of a recording made by [`RecordingTransport`]
+///
+/// Each call receives the next recorded response regardless of timing. Only
+/// the method is compared as params may be redacted. The id of the response is
+/// replaced with the id of the request.
+///
+/// Use with [`DelugeClientFactory::create_with_transport`](crate::DelugeClientFactory::create_with_transport).
+#[derive(Debug, Default)]
+pub struct ReplayTransport {
+    exchanges: VecDeque<Exchange>,
+}
+
+impl ReplayTransport {
+    /// Read a recording from a JSONL file
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let action = "read recording";
+        let text = read_to_string(path).map_err(|e| Error {
+            action: action.to_owned(),
+            domain: Some("file system".to_owned()),
+            message: format!("{}\n{e}", path.display()),
+            ..Error::default()
+        })?;
+        let exchanges = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|e| Error {
+                    action: action.to_owned(),
+                    domain: Some("deserialization".to_owned()),
+                    message: format!("Line {}: {e}", index + 1),
+                    ..Error::default()
+                })
+            })
+            .collect::<Result<Vec<Exchange>, Error>>()?;
+        Ok(Self::new(exchanges))
+    }
+
+    /// Serve recorded exchanges in order
+    #[must_use]
+    pub fn new(exchanges: Vec<Exchange>) -> Self {
+        Self {
+            exchanges: exchanges.into(),
+        }
+    }
+
+    /// Number of exchanges not yet served
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.exchanges.len()
+    }
+
+    fn next(&mut self, method: &str) -> Result<Exchange, Error> {
+        let action = format!("replay {method} request");
+        let Some(exchange) = self.exchanges.pop_front() else {
+            return Err(Error {
+                action,
+                domain: Some("replay".to_owned()),
+                message: "Recording has no more exchanges".to_owned(),
+                ..Error::default()
+            });
+        };
+        if exchange.method != method {
+            return Err(Error {
+                action,
+                domain: Some("replay".to_owned()),
+                message: format!("Recording expected {} request", exchange.method),
+                ..Error::default()
+            });
+        }
+        if let Some(message) = exchange.failure.clone() {
+            return Err(Error {
+                action,
+                domain: Some("replay".to_owned()),
+                message,
+                status_code: exchange.status_code,
+                ..Error::default()
+            });
+        }
+        Ok(exchange)
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send<'a>(
+        &'a mut self,
+        id: u32,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<Response<Value>, Error>> {
+        Box::pin(async move {
+            let mut response = self.send_raw(id, method, params).await?.parse(method)?;
+            response.id = usize::try_from(id).ok();
+            Ok(response)
+        })
+    }
+
+    /// Get the recorded body unchanged, including its recorded id
+    fn send_raw<'a>(
+        &'a mut self,
+        id: u32,
+        method: &'a str,
+        _params: Value,
+    ) -> BoxFuture<'a, Result<RawResponse, Error>> {
+        Box::pin(async move {
+            trace!("{} request {method} #{id}", "Replaying".bold());
+            let exchange = self.next(method)?;
+            Ok(RawResponse {
+                status_code: exchange.status_code,
+                body: exchange.body.unwrap_or_default(),
+            })
+        })
+    }
+
+    fn upload(
+        &mut self,
+        file_name: String,
+        _bytes: Vec<u8>,
+    ) -> BoxFuture<'_, Result<String, Error>> {
+        Box::pin(async move {
+            let exchange = self.next(UPLOAD)?;
+            exchange
+                .body
+                .and_then(|body| serde_json::from_str::<Response<String>>(&body).ok())
+                .and_then(|response| response.result)
+                .ok_or_else(|| Error {
+                    action: format!("replay upload of {file_name}"),
+                    domain: Some("replay".to_owned()),
+                    message: "Recording has no upload path".to_owned(),
+                    ..Error::default()
+                })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core_config::{CoreConfig, ProxyConfig};
+    use crate::testing::MockServer;
+    use crate::{DelugeClientFactory, DelugeClientOptions};
+    use std::env::temp_dir;
+    use std::fs::remove_file;
+
+    #[tokio::test]
+    async fn record_and_replay() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let port = server.host().replace(|x: char| !x.is_ascii_digit(), "");
+        let path = temp_dir().join(format!("deluge_api-recording-{port}.jsonl"));
+        let mut client = server.client().with_recording(&path)?;
+
+        let config = CoreConfig {
+            proxy: Some(ProxyConfig {
+                password: Some("proxy-secret".to_owned()),
+                ..ProxyConfig::default()
+            }),
+            ..CoreConfig::default()
+        };
+
+        // Act
+        client.login().await?;
+        let hosts = client.get_hosts().await?.get_result("get_hosts")?;
+        client.set_config(config).await?.check("set_config")?;
+        drop(client);
+        let recording = read_to_string(&path).expect("recording should be readable");
+        let factory = DelugeClientFactory {
+            options: server.options(),
+        };
+        let mut replay = factory.create_with_transport(ReplayTransport::open(&path)?);
+        remove_file(&path).expect("recording should be removed");
+        let login = replay.login().await?.get_result("login")?;
+        let replayed = replay.get_hosts().await?.get_result("get_hosts")?;
+        replay
+            .set_config(CoreConfig::default())
+            .await?
+            .check("set_config")?;
+        let exhausted = replay.get_hosts().await;
+
+        // Assert
+        assert_eq!(recording.lines().count(), 3);
+        assert!(!recording.contains(&server.options().password));
+        assert!(!recording.contains("proxy-secret"));
+        assert!(recording.contains(REDACTED));
+        assert!(login);
+        assert_eq!(replayed.len(), hosts.len());
+        assert_eq!(
+            replayed.first().map(|x| &x.id),
+            hosts.first().map(|x| &x.id)
+        );
+        assert!(exhausted.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn replay_malformed_body() {
+        // Arrange
+        let exchange = Exchange {
+            id: 1,
+            method: "web.get_hosts".to_owned(),
+            params: json!([]),
+            status_code: Some(502),
+            body: Some("<html>Bad Gateway</html>".to_owned()),
+            failure: None,
+            elapsed: 0.0,
+        };
+        let factory = DelugeClientFactory {
+            options: DelugeClientOptions::default(),
+        };
+        let mut client = factory.create_with_transport(ReplayTransport::new(vec![exchange]));
+
+        // Act
+        let result = client.get_hosts().await;
+
+        // Assert
+        let error = result.expect_err("should fail to parse");
+        assert_eq!(error.domain.as_deref(), Some("deserialization"));
+        assert_eq!(error.status_code, Some(502));
+    }
+
+    #[tokio::test]
+    async fn replay_method_mismatch() {
+        // Arrange
+        let exchange = Exchange {
+            id: 1,
+            method: "web.get_hosts".to_owned(),
+            params: json!([]),
+            status_code: Some(200),
+            body: Some(json!({ "result": [], "error": null, "id": 1 }).to_string()),
+            failure: None,
+            elapsed: 0.0,
+        };
+        let factory = DelugeClientFactory {
+            options: DelugeClientOptions::default(),
+        };
+        let mut client = factory.create_with_transport(ReplayTransport::new(vec![exchange]));
+
+        // Act
+        let result = client.get_labels().await;
+
+        // Assert
+        let error = result.expect_err("should be a mismatch");
+        assert!(error.message.contains("web.get_hosts"));
+    }
+}
@@ -0,0 +1,354 @@
+//! Encode and decode the rencode serialization format used by the Deluge daemon
+//!
+//! Only the types that can be represented as JSON are supported. Dictionary keys
+//! that are not strings, for example the integer ids of `AutoAdd` watch
+//! directories, are converted to strings as the web API does.
+//!
+//! # See Also
+//! - <https://github.com/aresch/rencode>
+use rogue_logging::Error;
+use serde_json::{Map, Number, Value};
+
+const CHR_LIST: u8 = 59;
+const CHR_DICT: u8 = 60;
+const CHR_INT: u8 = 61;
+const CHR_INT1: u8 = 62;
+const CHR_INT2: u8 = 63;
+const CHR_INT4: u8 = 64;
+const CHR_INT8: u8 = 65;
+const CHR_FLOAT32: u8 = 66;
+const CHR_FLOAT64: u8 = 44;
+const CHR_TRUE: u8 = 67;
+const CHR_FALSE: u8 = 68;
+const CHR_NONE: u8 = 69;
+const CHR_TERM: u8 = 127;
+
+const INT_POS_FIXED_START: u8 = 0;
+const INT_POS_FIXED_COUNT: u8 = 44;
+const INT_NEG_FIXED_START: u8 = 70;
+const INT_NEG_FIXED_COUNT: u8 = 32;
+const DICT_FIXED_START: u8 = 102;
+const DICT_FIXED_COUNT: u8 = 25;
+const STR_FIXED_START: u8 = 128;
+const STR_FIXED_COUNT: u8 = 64;
+const LIST_FIXED_START: u8 = STR_FIXED_START + STR_FIXED_COUNT;
+const LIST_FIXED_COUNT: u8 = 64;
+
+/// Encode a value
+///
+/// Floats are always encoded as 64 bit.
+pub(crate) fn encode(value: &Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    encode_value(value, &mut bytes);
+    bytes
+}
+
+/// Decode a value
+pub(crate) fn decode(bytes: &[u8]) -> Result<Value, Error> {
+    let mut decoder = Decoder { bytes, position: 0 };
+    let value = decoder.value()?;
+    if decoder.position != bytes.len() {
+        return Err(decode_error(format!(
+            "{} unexpected bytes after value",
+            bytes.len() - decoder.position
+        )));
+    }
+    Ok(value)
+}
+
+fn encode_value(value: &Value, bytes: &mut Vec<u8>) {
+    match value {
+        Value::Null => bytes.push(CHR_NONE),
+        Value::Bool(true) => bytes.push(CHR_TRUE),
+        Value::Bool(false) => bytes.push(CHR_FALSE),
+        Value::Number(number) => encode_number(number, bytes),
+        Value::String(string) => encode_str(string, bytes),
+        Value::Array(items) => {
+            let fixed = u8::try_from(items.len())
+                .ok()
+                .filter(|x| *x < LIST_FIXED_COUNT);
+            match fixed {
+                Some(len) => bytes.push(LIST_FIXED_START + len),
+                None => bytes.push(CHR_LIST),
+            }
+            for item in items {
+                encode_value(item, bytes);
+            }
+            if fixed.is_none() {
+                bytes.push(CHR_TERM);
+            }
+        }
+        Value::Object(map) => {
+            let fixed = u8::try_from(map.len())
+                .ok()
+                .filter(|x| *x < DICT_FIXED_COUNT);
+            match fixed {
+                Some(len) => bytes.push(DICT_FIXED_START + len),
+                None => bytes.push(CHR_DICT),
+            }
+            for (key, value) in map {
+                encode_str(key, bytes);
+                encode_value(value, bytes);
+            }
+            if fixed.is_none() {
+                bytes.push(CHR_TERM);
+            }
+        }
+    }
+}
+
+fn encode_number(number: &Number, bytes: &mut Vec<u8>) {
+    if let Some(int) = number.as_i64() {
+        if let Ok(fixed) = u8::try_from(int) {
+            if fixed < INT_POS_FIXED_COUNT {
+                bytes.push(INT_POS_FIXED_START + fixed);
+                return;
+            }
+        }
+        if let Some(fixed) = int.checked_neg().and_then(|x| u8::try_from(x).ok()) {
+            if (1..=INT_NEG_FIXED_COUNT).contains(&fixed) {
+                bytes.push(INT_NEG_FIXED_START - 1 + fixed);
+                return;
+            }
+        }
+        if let Ok(int) = i8::try_from(int) {
+            bytes.push(CHR_INT1);
+            bytes.extend(int.to_be_bytes());
+        } else if let Ok(int) = i16::try_from(int) {
+            bytes.push(CHR_INT2);
+            bytes.extend(int.to_be_bytes());
+        } else if let Ok(int) = i32::try_from(int) {
+            bytes.push(CHR_INT4);
+            bytes.extend(int.to_be_bytes());
+        } else {
+            bytes.push(CHR_INT8);
+            bytes.extend(int.to_be_bytes());
+        }
+    } else if let Some(int) = number.as_u64() {
+        bytes.push(CHR_INT);
+        bytes.extend(int.to_string().bytes());
+        bytes.push(CHR_TERM);
+    } else {
+        bytes.push(CHR_FLOAT64);
+        bytes.extend(number.as_f64().unwrap_or_default().to_be_bytes());
+    }
+}
+
+fn encode_str(string: &str, bytes: &mut Vec<u8>) {
+    match u8::try_from(string.len()) {
+        Ok(len) if len < STR_FIXED_COUNT => bytes.push(STR_FIXED_START + len),
+        _ => {
+            bytes.extend(string.len().to_string().bytes());
+            bytes.push(b':');
+        }
+    }
+    bytes.extend(string.bytes());
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Decoder<'_> {
+    fn value(&mut self) -> Result<Value, Error> {
+        let byte = self.byte()?;
+        let value = match byte {
+            CHR_NONE => Value::Null,
+            CHR_TRUE => Value::Bool(true),
+            CHR_FALSE => Value::Bool(false),
+            CHR_INT1 => Value::from(i8::from_be_bytes(self.array()?)),
+            CHR_INT2 => Value::from(i16::from_be_bytes(self.array()?)),
+            CHR_INT4 => Value::from(i32::from_be_bytes(self.array()?)),
+            CHR_INT8 => Value::from(i64::from_be_bytes(self.array()?)),
+            CHR_INT => {
+                let digits = self.until(CHR_TERM)?;
+                parse_int(&digits)?
+            }
+            CHR_FLOAT32 => Value::from(f64::from(f32::from_be_bytes(self.array()?))),
+            CHR_FLOAT64 => Value::from(f64::from_be_bytes(self.array()?)),
+            CHR_LIST => {
+                let mut items = Vec::new();
+                while self.peek()? != CHR_TERM {
+                    items.push(self.value()?);
+                }
+                self.position += 1;
+                Value::Array(items)
+            }
+            CHR_DICT => {
+                let mut map = Map::new();
+                while self.peek()? != CHR_TERM {
+                    let key = self.key()?;
+                    map.insert(key, self.value()?);
+                }
+                self.position += 1;
+                Value::Object(map)
+            }
+            b'0'..=b'9' => {
+                self.position -= 1;
+                let len = self.until(b':')?;
+                let len = len
+                    .parse::<usize>()
+                    .map_err(|e| decode_error(format!("Invalid string length: {e}")))?;
+                self.string(len)?
+            }
+            _ if byte < INT_POS_FIXED_START + INT_POS_FIXED_COUNT => {
+                Value::from(byte - INT_POS_FIXED_START)
+            }
+            _ if (INT_NEG_FIXED_START..INT_NEG_FIXED_START + INT_NEG_FIXED_COUNT)
+                .contains(&byte) =>
+            {
+                Value::from(-1 - i16::from(byte - INT_NEG_FIXED_START))
+            }
+            _ if (DICT_FIXED_START..DICT_FIXED_START + DICT_FIXED_COUNT).contains(&byte) => {
+                let mut map = Map::new();
+                for _ in 0..byte - DICT_FIXED_START {
+                    let key = self.key()?;
+                    map.insert(key, self.value()?);
+                }
+                Value::Object(map)
+            }
+            _ if (STR_FIXED_START..STR_FIXED_START + STR_FIXED_COUNT).contains(&byte) => {
+                self.string(usize::from(byte - STR_FIXED_START))?
+            }
+            _ if byte >= LIST_FIXED_START => {
+                let mut items = Vec::new();
+                for _ in 0..byte - LIST_FIXED_START {
+                    items.push(self.value()?);
+                }
+                Value::Array(items)
+            }
+            _ => return Err(decode_error(format!("Unexpected type code {byte}"))),
+        };
+        Ok(value)
+    }
+
+    /// Decode a dictionary key converting it to a string if necessary
+    fn key(&mut self) -> Result<String, Error> {
+        match self.value()? {
+            Value::String(key) => Ok(key),
+            key => Ok(key.to_string()),
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        let byte = self.peek()?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn peek(&self) -> Result<u8, Error> {
+        self.bytes
+            .get(self.position)
+            .copied()
+            .ok_or_else(|| decode_error("Unexpected end of data".to_owned()))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&[u8], Error> {
+        let end = self.position + len;
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .ok_or_else(|| decode_error("Unexpected end of data".to_owned()))?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let bytes = self.take(N)?;
+        bytes
+            .try_into()
+            .map_err(|_| decode_error("Unexpected end of data".to_owned()))
+    }
+
+    fn until(&mut self, terminator: u8) -> Result<String, Error> {
+        let remaining = self.bytes.get(self.position..).unwrap_or_default();
+        let len = remaining
+            .iter()
+            .position(|x| *x == terminator)
+            .ok_or_else(|| decode_error("Unterminated value".to_owned()))?;
+        let text = String::from_utf8_lossy(self.take(len)?).into_owned();
+        self.position += 1;
+        Ok(text)
+    }
+
+    fn string(&mut self, len: usize) -> Result<Value, Error> {
+        let bytes = self.take(len)?;
+        Ok(Value::String(String::from_utf8_lossy(bytes).into_owned()))
+    }
+}
+
+fn parse_int(digits: &str) -> Result<Value, Error> {
+    if let Ok(int) = digits.parse::<i64>() {
+        return Ok(Value::from(int));
+    }
+    if let Ok(int) = digits.parse::<u64>() {
+        return Ok(Value::from(int));
+    }
+    digits
+        .parse::<f64>()
+        .map(Value::from)
+        .map_err(|e| decode_error(format!("Invalid integer {digits}: {e}")))
+}
+
+fn decode_error(message: String) -> Error {
+    Error {
+        action: "decode rencode message".to_owned(),
+        domain: Some("deserialization".to_owned()),
+        message,
+        ..Error::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn encode_known_bytes() {
+        assert_eq!(encode(&json!(null)), vec![CHR_NONE]);
+        assert_eq!(encode(&json!(5)), vec![5]);
+        assert_eq!(encode(&json!(-1)), vec![70]);
+        assert_eq!(encode(&json!(-32)), vec![101]);
+        assert_eq!(encode(&json!(100)), vec![CHR_INT1, 100]);
+        assert_eq!(encode(&json!(1000)), vec![CHR_INT2, 3, 232]);
+        assert_eq!(encode(&json!("ab")), vec![130, b'a', b'b']);
+        assert_eq!(encode(&json!([1, true])), vec![194, 1, CHR_TRUE]);
+        assert_eq!(encode(&json!({"a": 1})), vec![103, 129, b'a', 1]);
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        // Arrange
+        let long_list: Vec<u32> = (0..100).collect();
+        let value = json!({
+            "string": "example",
+            "long_string": "x".repeat(100),
+            "ints": [0, 43, 44, -1, -32, -33, 127, -128, 32767, 70000, -3_000_000_000_i64],
+            "big": u64::MAX,
+            "float": 1.5,
+            "bools": [true, false, null],
+            "long_list": long_list,
+            "nested": {"a": [{"b": []}]}
+        });
+
+        // Act
+        let decoded = decode(&encode(&value)).expect("should decode");
+
+        // Assert
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn decode_python_types() {
+        // Integer keys are converted to strings
+        let decoded = decode(&[103, 1, 130, b'o', b'k']).expect("should decode");
+        assert_eq!(decoded, json!({"1": "ok"}));
+        // Python encodes floats as 32 bit by default
+        let decoded = decode(&[CHR_FLOAT32, 63, 192, 0, 0]).expect("should decode");
+        assert_eq!(decoded, json!(1.5));
+        assert!(decode(&[CHR_INT2, 3]).is_err());
+        assert!(decode(&[5, 5]).is_err());
+    }
+}
@@ -12,14 +12,18 @@ pub struct Response<T> {
 }
 
 impl<T: Serialize> Response<T> {
-    /// Get the result
+    /// Check the response succeeded without requiring a result
+    ///
+    /// Use this rather than [`Response::get_result`] for methods where Deluge
+    /// returns `null` on success, which includes most methods that return
+    /// `Response<()>`.
     ///
     /// Returns an error if:
     /// - Error field is some
     /// - Status code is not set
     /// - Status code is not valid
     /// - Status code is not successful
-    pub fn get_result(self, action: &str) -> Result<T, Error> {
+    pub fn check(&self, action: &str) -> Result<(), Error> {
         if let Some(error) = self.error.clone() {
             return Err(Error {
                 action: action.to_owned(),
@@ -56,6 +60,19 @@ impl<T: Serialize> Response<T> {
                 ..Error::default()
             });
         }
+        Ok(())
+    }
+
+    /// Get the result
+    ///
+    /// Returns an error if:
+    /// - Error field is some
+    /// - Status code is not set
+    /// - Status code is not valid
+    /// - Status code is not successful
+    /// - Result is not set
+    pub fn get_result(self, action: &str) -> Result<T, Error> {
+        self.check(action)?;
         if let Some(result) = self.result {
             Ok(result)
         } else {
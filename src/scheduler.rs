@@ -0,0 +1,237 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use std::ops::Range;
+
+/// Number of days in a [`WeeklySchedule`]
+pub const DAYS: usize = 7;
+
+/// Number of hours in each day of a [`WeeklySchedule`]
+pub const HOURS: usize = 24;
+
+impl DelugeClient {
+    /// Get the Scheduler plugin configuration.
+    ///
+    /// Requires the Scheduler plugin to be enabled.
+    pub async fn get_scheduler_config(&mut self) -> Result<Response<SchedulerConfig>, Error> {
+        self.call("scheduler.get_config", ()).await
+    }
+
+    /// Set the Scheduler plugin configuration.
+    pub async fn set_scheduler_config(
+        &mut self,
+        config: SchedulerConfig,
+    ) -> Result<Response<()>, Error> {
+        self.call("scheduler.set_config", (config,)).await
+    }
+
+    /// Get the slot the scheduler is currently applying.
+    pub async fn get_scheduler_state(&mut self) -> Result<Response<Slot>, Error> {
+        self.call("scheduler.get_state", ()).await
+    }
+}
+
+/// Scheduler plugin configuration
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SchedulerConfig {
+    /// Maximum download speed in KiB/s during [`Slot::Yellow`], `-1` for unlimited
+    pub low_down: f64,
+    /// Maximum upload speed in KiB/s during [`Slot::Yellow`], `-1` for unlimited
+    pub low_up: f64,
+    /// Maximum number of active torrents during [`Slot::Yellow`], `-1` for unlimited
+    pub low_active: i64,
+    /// Maximum number of active downloading torrents during [`Slot::Yellow`], `-1` for unlimited
+    pub low_active_down: i64,
+    /// Maximum number of active seeding torrents during [`Slot::Yellow`], `-1` for unlimited
+    pub low_active_up: i64,
+    /// Slot for each hour of the week
+    pub button_state: WeeklySchedule,
+}
+
+/// Slot for each hour of the week
+///
+/// Stored as 24 rows of 7 days, the `button_state[hour][weekday]` layout of the
+/// Scheduler plugin. Days are indexed from `0` for Monday to `6` for Sunday and
+/// hours from `0` to `23` in the daemon's local time.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct WeeklySchedule(pub [[Slot; DAYS]; HOURS]);
+
+impl WeeklySchedule {
+    /// Create a schedule with every hour set to `slot`
+    #[must_use]
+    pub fn new(slot: Slot) -> Self {
+        Self([[slot; DAYS]; HOURS])
+    }
+
+    /// Get the slot of an hour
+    ///
+    /// Returns `None` if `day` or `hour` is out of range.
+    #[must_use]
+    pub fn get(&self, day: usize, hour: usize) -> Option<Slot> {
+        self.0.get(hour)?.get(day).copied()
+    }
+
+    /// Set the slot of an hour
+    ///
+    /// Out of range values are ignored.
+    pub fn set(&mut self, day: usize, hour: usize, slot: Slot) {
+        if let Some(value) = self.0.get_mut(hour).and_then(|x| x.get_mut(day)) {
+            *value = slot;
+        }
+    }
+
+    /// Set the slot of every hour in `hours` on every day in `days`
+    ///
+    /// Out of range values are ignored.
+    ///
+    /// # Examples
+    /// Limit speeds during office hours on weekdays:
+    /// ```
+    /// use deluge_api::scheduler::{Slot, WeeklySchedule};
+    /// let mut schedule = WeeklySchedule::new(Slot::Green);
+    /// schedule.fill(0..5, 9..17, Slot::Yellow);
+    /// ```
+    pub fn fill(&mut self, days: Range<usize>, hours: Range<usize>, slot: Slot) {
+        for day in days {
+            for hour in hours.clone() {
+                self.set(day, hour, slot);
+            }
+        }
+    }
+}
+
+impl Default for WeeklySchedule {
+    fn default() -> Self {
+        Self::new(Slot::Green)
+    }
+}
+
+/// Scheduler state for an hour
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Slot {
+    /// Normal speed limits
+    #[default]
+    Green,
+    /// Limited speeds from the [`SchedulerConfig`]
+    Yellow,
+    /// Paused
+    Red,
+}
+
+impl<'de> Deserialize<'de> for Slot {
+    /// Deserialize from either the `0`, `1`, `2` format of the button state
+    /// or the `Green`, `Yellow`, `Red` format of the current state
+    #[allow(clippy::absolute_paths)]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value: Value = Deserialize::deserialize(deserializer)?;
+        match value {
+            Value::Number(number) => match number.as_u64() {
+                Some(0) => Ok(Slot::Green),
+                Some(1) => Ok(Slot::Yellow),
+                Some(2) => Ok(Slot::Red),
+                _ => Err(serde::de::Error::custom(format!("Invalid slot: {number}"))),
+            },
+            Value::String(name) => match name.as_str() {
+                "Green" => Ok(Slot::Green),
+                "Yellow" => Ok(Slot::Yellow),
+                "Red" => Ok(Slot::Red),
+                _ => Err(serde::de::Error::custom(format!("Invalid slot: {name}"))),
+            },
+            _ => Err(serde::de::Error::custom(
+                "Expected a number or string for slot",
+            )),
+        }
+    }
+}
+
+impl Serialize for Slot {
+    /// Serialize to the `0`, `1`, `2` format of the button state
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Slot::Green => serializer.serialize_u8(0),
+            Slot::Yellow => serializer.serialize_u8(1),
+            Slot::Red => serializer.serialize_u8(2),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use log::trace;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn get_scheduler_config() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_scheduler_config().await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let _result = response.get_result("get_scheduler_config")?;
+        Ok(())
+    }
+
+    #[test]
+    fn weekly_schedule_fill() {
+        // Arrange
+        let mut schedule = WeeklySchedule::default();
+
+        // Act
+        schedule.fill(0..5, 9..17, Slot::Yellow);
+        let json = serde_json::to_value(&schedule).expect("should serialize");
+        let deserialized: WeeklySchedule =
+            serde_json::from_value(json.clone()).expect("should deserialize");
+
+        // Assert
+        assert_eq!(schedule.get(0, 8), Some(Slot::Green));
+        assert_eq!(schedule.get(0, 9), Some(Slot::Yellow));
+        assert_eq!(schedule.get(4, 16), Some(Slot::Yellow));
+        assert_eq!(schedule.get(5, 12), Some(Slot::Green));
+        assert_eq!(schedule.get(7, 0), None);
+        assert_eq!(json.pointer("/9/0"), Some(&Value::from(1)));
+        assert_eq!(json.pointer("/9/5"), Some(&Value::from(0)));
+        assert_eq!(deserialized, schedule);
+    }
+
+    #[test]
+    fn deserialize_button_state() {
+        // Arrange
+        // `[[0] * 7 for _ in range(24)]` with Saturday 02:00 set to red
+        let mut rows = vec![vec![0; DAYS]; HOURS];
+        if let Some(slot) = rows.get_mut(2).and_then(|x| x.get_mut(5)) {
+            *slot = 2;
+        }
+        let json = serde_json::json!({
+            "low_down": 100.0,
+            "low_up": 50.0,
+            "low_active": -1,
+            "low_active_down": -1,
+            "low_active_up": -1,
+            "button_state": rows,
+        });
+
+        // Act
+        let config: SchedulerConfig = serde_json::from_value(json).expect("should deserialize");
+
+        // Assert
+        assert_eq!(config.button_state.get(5, 2), Some(Slot::Red));
+        assert_eq!(config.button_state.get(2, 5), Some(Slot::Green));
+        assert_eq!(config.button_state.get(6, 23), Some(Slot::Green));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+impl DelugeClient {
+    /// Get the session status values for `keys`.
+    ///
+    /// Use [`SessionStatus::keys`] to request every modelled value. Keys that are
+    /// not modelled are returned in [`SessionStatus::other`].
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_session_status(
+        &mut self,
+        keys: Vec<String>,
+    ) -> Result<Response<SessionStatus>, Error> {
+        self.call("core.get_session_status", (keys,)).await
+    }
+
+    /// Get the port libtorrent is listening on for incoming connections.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_listen_port(&mut self) -> Result<Response<u16>, Error> {
+        self.call("core.get_listen_port", ()).await
+    }
+
+    /// Check if the listen port is reachable from the internet.
+    ///
+    /// The check is made by a service hosted by the Deluge project.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn test_listen_port(&mut self) -> Result<Response<bool>, Error> {
+        self.call("core.test_listen_port", ()).await
+    }
+
+    /// Get the external IP address reported by libtorrent.
+    ///
+    /// The result is empty until a peer or tracker has reported it.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_external_ip(&mut self) -> Result<Response<String>, Error> {
+        self.call("core.get_external_ip", ()).await
+    }
+
+    /// Get the version of libtorrent used by the daemon.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn get_libtorrent_version(&mut self) -> Result<Response<String>, Error> {
+        self.call("core.get_libtorrent_version", ()).await
+    }
+
+    /// Get the version of the daemon.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn daemon_info(&mut self) -> Result<Response<String>, Error> {
+        self.call("daemon.info", ()).await
+    }
+}
+
+/// Session status of the daemon
+///
+/// Only the requested keys are set. Rates are in bytes per second and totals
+/// are in bytes.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SessionStatus {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upload_rate: Option<f64>,
+    /// Download rate excluding protocol overhead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload_download_rate: Option<f64>,
+    /// Upload rate excluding protocol overhead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload_upload_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_download: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_upload: Option<u64>,
+    /// Bytes downloaded excluding protocol overhead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_payload_download: Option<u64>,
+    /// Bytes uploaded excluding protocol overhead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_payload_upload: Option<u64>,
+    /// Number of nodes in the DHT routing table
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dht_nodes: Option<u64>,
+    /// Number of connected peers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_peers: Option<u64>,
+    /// `1` if a peer has connected to the listen port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_incoming_connections: Option<u64>,
+    /// Ratio of disk reads served from the cache
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_hit_ratio: Option<f64>,
+    /// Ratio of disk writes absorbed by the cache
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_hit_ratio: Option<f64>,
+    /// Number of 16 KiB blocks in the disk cache
+    #[serde(
+        rename = "disk.disk_blocks_in_use",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub disk_blocks_in_use: Option<u64>,
+    /// Number of blocks read from disk
+    #[serde(
+        rename = "disk.num_blocks_read",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub disk_blocks_read: Option<u64>,
+    /// Number of blocks written to disk
+    #[serde(
+        rename = "disk.num_blocks_written",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub disk_blocks_written: Option<u64>,
+    /// Values that are not modelled keyed by libtorrent counter name
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+impl SessionStatus {
+    /// Keys of every modelled value
+    #[must_use]
+    pub fn keys() -> Vec<String> {
+        [
+            "download_rate",
+            "upload_rate",
+            "payload_download_rate",
+            "payload_upload_rate",
+            "total_download",
+            "total_upload",
+            "total_payload_download",
+            "total_payload_upload",
+            "dht_nodes",
+            "num_peers",
+            "has_incoming_connections",
+            "read_hit_ratio",
+            "write_hit_ratio",
+            "disk.disk_blocks_in_use",
+            "disk.num_blocks_read",
+            "disk.num_blocks_written",
+        ]
+        .into_iter()
+        .map(str::to_owned)
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+
+    #[tokio::test]
+    async fn session_status() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+        let mut keys = SessionStatus::keys();
+        keys.push("peer.num_peers_half_open".to_owned());
+
+        // Act
+        client.login().await?;
+        let status = client.get_session_status(keys).await?;
+        let port = client.get_listen_port().await?;
+        let version = client.daemon_info().await?;
+
+        // Assert
+        let status = status.get_result("get_session_status")?;
+        assert_eq!(status.dht_nodes, Some(0));
+        assert_eq!(status.disk_blocks_in_use, Some(0));
+        assert!(status.other.contains_key("peer.num_peers_half_open"));
+        assert_eq!(port.get_result("get_listen_port")?, 6881);
+        assert_eq!(version.get_result("daemon_info")?, "2.1.1");
+        Ok(())
+    }
+}
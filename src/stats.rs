@@ -0,0 +1,175 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+use std::collections::HashMap;
+
+impl DelugeClient {
+    /// Get the time series of `keys` sampled every `interval` seconds.
+    ///
+    /// Requires the Stats plugin to be enabled.
+    ///
+    /// `interval` must be one of [`DelugeClient::get_stats_intervals`] otherwise
+    /// Deluge returns `null`.
+    pub async fn get_stats(
+        &mut self,
+        keys: Vec<StatKey>,
+        interval: u32,
+    ) -> Result<Response<Stats>, Error> {
+        self.call("stats.get_stats", (keys, interval)).await
+    }
+
+    /// Get the transfer totals of all sessions.
+    pub async fn get_stats_totals(&mut self) -> Result<Response<Totals>, Error> {
+        self.call("stats.get_totals", ()).await
+    }
+
+    /// Get the transfer totals of the current session.
+    pub async fn get_session_totals(&mut self) -> Result<Response<Totals>, Error> {
+        self.call("stats.get_session_totals", ()).await
+    }
+
+    /// Get the sample intervals in seconds.
+    pub async fn get_stats_intervals(&mut self) -> Result<Response<Vec<u32>>, Error> {
+        self.call("stats.get_intervals", ()).await
+    }
+}
+
+/// Time series sampled at a fixed interval
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Stats {
+    /// Unix timestamp of the most recent sample
+    #[serde(rename = "_last_update")]
+    pub last_update: f64,
+    /// Maximum number of samples in each series
+    #[serde(rename = "_length")]
+    pub length: u32,
+    /// Seconds between samples
+    #[serde(rename = "_update_interval")]
+    pub update_interval: u32,
+    /// Samples of each requested key, newest first
+    #[serde(flatten)]
+    pub series: HashMap<String, Vec<f64>>,
+}
+
+impl Stats {
+    /// Get the samples of a key, newest first
+    #[must_use]
+    pub fn get(&self, key: StatKey) -> Option<&Vec<f64>> {
+        self.series.get(key.as_str())
+    }
+
+    /// Get the samples of a key paired with their Unix timestamp, newest first
+    #[must_use]
+    #[allow(clippy::as_conversions, clippy::cast_precision_loss)]
+    pub fn get_timestamped(&self, key: StatKey) -> Option<Vec<(f64, f64)>> {
+        let interval = f64::from(self.update_interval);
+        let series = self.get(key)?;
+        let values = series
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (self.last_update - index as f64 * interval, *value))
+            .collect();
+        Some(values)
+    }
+}
+
+/// Transfer totals in bytes
+///
+/// Keys that are not modelled are kept in `other`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Totals {
+    pub total_upload: u64,
+    pub total_download: u64,
+    pub total_payload_upload: u64,
+    pub total_payload_download: u64,
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+/// Key of a time series recorded by the Stats plugin
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StatKey {
+    /// Total upload rate in bytes per second
+    UploadRate,
+    /// Total download rate in bytes per second
+    DownloadRate,
+    /// Payload upload rate in bytes per second
+    PayloadUploadRate,
+    /// Payload download rate in bytes per second
+    PayloadDownloadRate,
+    /// Protocol overhead upload rate in bytes per second
+    UploadProtocolRate,
+    /// Protocol overhead download rate in bytes per second
+    DownloadProtocolRate,
+    /// Number of nodes in the DHT routing table
+    DhtNodes,
+    /// Number of nodes in the DHT cache
+    DhtCacheNodes,
+    /// Number of torrents tracked by the DHT
+    DhtTorrents,
+    /// Number of connected peers
+    NumPeers,
+    /// Number of connections
+    NumConnections,
+}
+
+impl StatKey {
+    /// Key as used by the Stats plugin
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatKey::UploadRate => "upload_rate",
+            StatKey::DownloadRate => "download_rate",
+            StatKey::PayloadUploadRate => "payload_upload_rate",
+            StatKey::PayloadDownloadRate => "payload_download_rate",
+            StatKey::UploadProtocolRate => "upload_protocol_rate",
+            StatKey::DownloadProtocolRate => "download_protocol_rate",
+            StatKey::DhtNodes => "dht_nodes",
+            StatKey::DhtCacheNodes => "dht_cache_nodes",
+            StatKey::DhtTorrents => "dht_torrents",
+            StatKey::NumPeers => "num_peers",
+            StatKey::NumConnections => "num_connections",
+        }
+    }
+}
+
+impl Serialize for StatKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockServer;
+    use log::trace;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn get_stats() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+        let keys = vec![StatKey::UploadRate, StatKey::DownloadRate];
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_stats_intervals().await?;
+        trace!("{}", response.to_json_pretty());
+        let intervals = response.get_result("get_stats_intervals")?;
+        let interval = *intervals.first().expect("should be at least one interval");
+        let response = client.get_stats(keys, interval).await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_stats")?;
+        assert!(result.get(StatKey::UploadRate).is_some());
+        Ok(())
+    }
+}
@@ -0,0 +1,1639 @@
+//! In-process mock of the Deluge Web API for tests
+//!
+//! [`MockServer`] serves the `/json` and `/upload` endpoints from an in-memory
+//! [`MockState`] so code using [`DelugeClient`] can be tested without Deluge.
+//!
+//! Requires the `testing` feature.
+use crate::{DelugeClient, DelugeClientFactory, DelugeClientOptions, State};
+use bytes::Bytes;
+use colored::Colorize;
+use futures::future::pending;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::header::{CONTENT_TYPE, COOKIE, SET_COOKIE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, StatusCode};
+use hyper_util::rt::TokioIo;
+use log::*;
+use rogue_logging::Error;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+
+const SESSION_COOKIE: &str = "_session_id";
+const DEFAULT_PASSWORD: &str = "deluge";
+const DEFAULT_VERSION: &str = "2.1.1";
+/// Namespaces of the plugins handled by `MockState::call_plugin`
+const PLUGIN_NAMESPACES: [&str; 7] = [
+    "autoadd",
+    "blocklist",
+    "execute",
+    "extractor",
+    "notifications",
+    "scheduler",
+    "stats",
+];
+/// Sample intervals in seconds of the Stats plugin
+const STATS_INTERVALS: [u64; 4] = [1, 5, 30, 300];
+
+/// Error code of a call made without logging in
+pub const ERROR_NOT_AUTHENTICATED: u64 = 1;
+/// Error code of a call to a method that does not exist
+pub const ERROR_UNKNOWN_METHOD: u64 = 2;
+/// Error code of a call that raised an exception
+pub const ERROR_EXCEPTION: u64 = 3;
+
+/// A mock Deluge Web API server listening on a random local port
+///
+/// The server stops when it is dropped.
+pub struct MockServer {
+    address: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Start a server with the default [`MockState`]
+    pub async fn start() -> Result<Self, Error> {
+        Self::start_with(MockState::default()).await
+    }
+
+    /// Start a server with an initial state
+    pub async fn start_with(state: MockState) -> Result<Self, Error> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| start_error(e.to_string()))?;
+        let address = listener
+            .local_addr()
+            .map_err(|e| start_error(e.to_string()))?;
+        let state = Arc::new(Mutex::new(state));
+        let handle = tokio::spawn(serve(listener, state.clone()));
+        trace!("{} mock server at {address}", "Started".bold());
+        Ok(Self {
+            address,
+            state,
+            handle,
+        })
+    }
+
+    /// Host to use for [`DelugeClientOptions::host`]
+    #[must_use]
+    pub fn host(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Options to connect to the server
+    #[must_use]
+    pub fn options(&self) -> DelugeClientOptions {
+        DelugeClientOptions {
+            host: self.host(),
+            password: self.state().password.clone(),
+            rate_limit_count: Some(1000),
+            rate_limit_duration: Some(1),
+            ..DelugeClientOptions::default()
+        }
+    }
+
+    /// Create a client connected to the server
+    #[must_use]
+    pub fn client(&self) -> DelugeClient {
+        let factory = DelugeClientFactory {
+            options: self.options(),
+        };
+        factory.create()
+    }
+
+    /// Lock the state to read or modify it
+    ///
+    /// Do not hold the lock while awaiting a request to the server.
+    pub fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().expect("lock should not be poisoned")
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// In-memory session of a [`MockServer`]
+#[derive(Clone, Debug)]
+pub struct MockState {
+    /// Password accepted by `auth.login`
+    pub password: String,
+    pub hosts: Vec<MockHost>,
+    /// Id of the connected host
+    pub connected: Option<String>,
+    /// Torrents keyed by id
+    pub torrents: BTreeMap<String, MockTorrent>,
+    /// Label options keyed by label
+    pub labels: BTreeMap<String, Value>,
+    /// Core config returned by `core.get_config`
+    pub config: Map<String, Value>,
+    /// Web interface config returned by `web.get_config`
+    pub web_config: Map<String, Value>,
+    /// Plugin configs returned by `<namespace>.get_config` keyed by namespace
+    pub plugin_configs: BTreeMap<String, Map<String, Value>>,
+    /// `AutoAdd` watch folder options keyed by id
+    pub watchdirs: BTreeMap<u32, Value>,
+    /// Execute commands as `[ id, event, command ]`
+    pub commands: Vec<Value>,
+    pub enabled_plugins: Vec<String>,
+    pub available_plugins: Vec<String>,
+    /// Errors returned instead of the result, keyed by method
+    pub errors: HashMap<String, Value>,
+    /// Bytes returned by `core.get_free_space` for every path
+    ///
+    /// Set to `-1` to respond as Deluge does for a path that does not exist.
+    pub free_space: i64,
+    /// Delay before every response
+    pub latency: Duration,
+    /// Never respond to `web.add_torrents` if a torrent is already in the
+    /// session, as Deluge does
+    ///
+    /// Otherwise an error is returned.
+    pub hang_on_duplicate: bool,
+    /// Calls in the order they were received
+    pub calls: Vec<MockCall>,
+    /// Paths of uploaded files
+    pub uploads: Vec<String>,
+    sessions: HashSet<String>,
+    logins: usize,
+    listeners: HashSet<String>,
+    events: Vec<Value>,
+}
+
+impl Default for MockState {
+    fn default() -> Self {
+        let mut config = Map::new();
+        config.insert("download_location".to_owned(), json!("/downloads"));
+        config.insert("max_active_downloading".to_owned(), json!(3));
+        config.insert("max_active_seeding".to_owned(), json!(5));
+        config.insert("max_download_speed".to_owned(), json!(-1.0));
+        config.insert("max_upload_speed".to_owned(), json!(-1.0));
+        Self {
+            password: DEFAULT_PASSWORD.to_owned(),
+            hosts: vec![MockHost::default()],
+            connected: Some(MockHost::default().id),
+            torrents: BTreeMap::new(),
+            labels: BTreeMap::new(),
+            config,
+            web_config: default_web_config(),
+            plugin_configs: default_plugin_configs(),
+            watchdirs: BTreeMap::new(),
+            commands: Vec::new(),
+            enabled_plugins: vec!["Label".to_owned()],
+            available_plugins: vec!["Label".to_owned(), "Scheduler".to_owned()],
+            errors: HashMap::new(),
+            free_space: 1_000_000_000_000,
+            latency: Duration::ZERO,
+            hang_on_duplicate: true,
+            calls: Vec::new(),
+            uploads: Vec::new(),
+            sessions: HashSet::new(),
+            logins: 0,
+            listeners: HashSet::new(),
+            events: Vec::new(),
+        }
+    }
+}
+
+/// A call received by a [`MockServer`]
+#[derive(Clone, Debug)]
+pub struct MockCall {
+    pub method: String,
+    pub params: Vec<Value>,
+}
+
+/// A daemon listed by `web.get_hosts`
+#[derive(Clone, Debug)]
+pub struct MockHost {
+    pub id: String,
+    pub host: String,
+    pub port: u16,
+    pub user: String,
+    pub version: String,
+}
+
+impl Default for MockHost {
+    fn default() -> Self {
+        Self {
+            id: "0f5c5f5b6e0f4c3a9d3c0b8f2f8b1a2e".to_owned(),
+            host: "127.0.0.1".to_owned(),
+            port: 58846,
+            user: "localclient".to_owned(),
+            version: DEFAULT_VERSION.to_owned(),
+        }
+    }
+}
+
+/// A torrent in the session of a [`MockServer`]
+#[derive(Clone, Debug)]
+pub struct MockTorrent {
+    pub name: String,
+    pub state: State,
+    pub label: String,
+    pub owner: String,
+    pub tracker_host: String,
+    pub save_path: String,
+    /// Percentage downloaded
+    pub progress: f64,
+    pub ratio: f64,
+    pub total_size: u64,
+    /// Seconds spent seeding
+    pub seeding_time: u64,
+    /// Unix timestamp the download completed, `0` if not completed
+    pub completed_time: u64,
+    /// Unix timestamp the torrent was added
+    pub time_added: u64,
+}
+
+impl Default for MockTorrent {
+    fn default() -> Self {
+        Self {
+            name: "example".to_owned(),
+            state: State::Seeding,
+            label: String::new(),
+            owner: "localclient".to_owned(),
+            tracker_host: "example.com".to_owned(),
+            save_path: "/downloads".to_owned(),
+            progress: 100.0,
+            ratio: 0.0,
+            total_size: 1_048_576,
+            seeding_time: 0,
+            completed_time: 0,
+            time_added: 0,
+        }
+    }
+}
+
+impl MockTorrent {
+    /// Every status key returned by `web.get_torrent_status` and `web.update_ui`
+    #[must_use]
+    #[allow(
+        clippy::as_conversions,
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn status(&self) -> Value {
+        let total_done = (self.total_size as f64 * self.progress / 100.0) as u64;
+        json!({
+            "completed_time": self.completed_time,
+            "distributed_copies": 0.0,
+            "download_location": self.save_path,
+            "download_payload_rate": 0.0,
+            "eta": 0.0,
+            "is_auto_managed": true,
+            "label": self.label,
+            "last_seen_complete": 0,
+            "max_download_speed": -1,
+            "max_upload_speed": -1,
+            "name": self.name,
+            "num_peers": 0,
+            "num_seeds": 0,
+            "owner": self.owner,
+            "progress": self.progress,
+            "queue": -1,
+            "ratio": self.ratio,
+            "save_path": self.save_path,
+            "seeding_time": self.seeding_time,
+            "seeds_peers_ratio": 0.0,
+            "state": self.state,
+            "time_added": self.time_added,
+            "time_since_transfer": 0,
+            "total_done": total_done,
+            "total_peers": 0,
+            "total_remaining": self.total_size.saturating_sub(total_done),
+            "total_seeds": 0,
+            "total_size": self.total_size,
+            "total_uploaded": 0,
+            "total_wanted": self.total_size,
+            "tracker_host": self.tracker_host,
+            "upload_payload_rate": 0.0,
+        })
+    }
+}
+
+impl MockState {
+    /// Return an error for every call of a method
+    pub fn inject_error(&mut self, method: &str, message: &str) {
+        self.errors
+            .insert(method.to_owned(), error(ERROR_EXCEPTION, message));
+    }
+
+    /// End every session as if it expired so calls fail until `auth.login`
+    pub fn expire_sessions(&mut self) {
+        self.sessions.clear();
+    }
+
+    /// Queue an event for `web.get_events` if a listener is registered for it
+    pub fn emit_event(&mut self, name: &str, args: Value) {
+        if self.listeners.contains(name) {
+            self.events.push(json!([name, args]));
+        }
+    }
+
+    fn handle_json(&mut self, request: &Value, session: Option<String>) -> Reply {
+        let id = request.get("id").cloned().unwrap_or_default();
+        let method = request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_owned();
+        let params = request
+            .get("params")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        trace!("{} mock request {method}", "Received".bold());
+        self.calls.push(MockCall {
+            method: method.clone(),
+            params: params.clone(),
+        });
+        let mut cookie = None;
+        let outcome = if let Some(error) = self.errors.get(&method) {
+            Outcome::Error(error.clone())
+        } else if method == "auth.login" {
+            let success = param(&params, 0).as_str() == Some(self.password.as_str());
+            if success {
+                self.logins += 1;
+                let session = format!("{:032x}", self.logins);
+                cookie = Some(format!("{SESSION_COOKIE}={session}; Path=/"));
+                self.sessions.insert(session);
+            }
+            Outcome::Result(Value::Bool(success))
+        } else if session.is_some_and(|x| self.sessions.contains(&x)) {
+            self.call(&method, &params)
+        } else {
+            Outcome::Error(error(ERROR_NOT_AUTHENTICATED, "Not authenticated"))
+        };
+        let body = match outcome {
+            Outcome::Result(result) => json!({ "result": result, "error": null, "id": id }),
+            Outcome::Error(error) => json!({ "result": null, "error": error, "id": id }),
+            Outcome::Hang => {
+                return Reply {
+                    hang: true,
+                    ..Reply::default()
+                }
+            }
+        };
+        Reply {
+            body,
+            cookie,
+            ..Reply::default()
+        }
+    }
+
+    fn handle_upload(&mut self, body: &[u8]) -> Reply {
+        let body = String::from_utf8_lossy(body);
+        let file_name = body
+            .split("filename=\"")
+            .nth(1)
+            .and_then(|x| x.split('"').next())
+            .unwrap_or("upload");
+        let path = format!("/tmp/delugeweb-mock/{file_name}");
+        self.uploads.push(path.clone());
+        Reply {
+            body: json!({ "success": true, "files": [path] }),
+            ..Reply::default()
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn call(&mut self, method: &str, params: &[Value]) -> Outcome {
+        if let Some(outcome) = self.call_plugin(method, params) {
+            return outcome;
+        }
+        let result = match method {
+            "auth.check_session" | "core.test_listen_port" => json!(true),
+            "web.connected" => json!(self.connected.is_some()),
+            "web.get_hosts" => self
+                .hosts
+                .iter()
+                .map(|x| json!([x.id, x.host, x.port, x.user]))
+                .collect(),
+            "web.get_host_status" => {
+                let id = param_str(params, 0);
+                let Some(host) = self.hosts.iter().find(|x| x.id == id) else {
+                    return exception(&format!("Unknown host {id}"));
+                };
+                let status = if self.connected.as_deref() == Some(id.as_str()) {
+                    "Connected"
+                } else {
+                    "Online"
+                };
+                json!([host.id, status, host.version])
+            }
+            "web.connect" => {
+                self.connected = Some(param_str(params, 0));
+                Value::Null
+            }
+            "web.disconnect" => {
+                self.connected = None;
+                json!(true)
+            }
+            "web.update_ui" => self.update_ui(&param(params, 1)),
+            "web.get_torrent_status" => self
+                .torrents
+                .get(&param_str(params, 0))
+                .map_or_else(|| json!({}), MockTorrent::status),
+            "web.add_torrents" => return self.add_torrents(&param(params, 0)),
+            "web.get_torrent_info" => {
+                let path = param_str(params, 0);
+                let name = path
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or_default()
+                    .trim_end_matches(".torrent")
+                    .to_owned();
+                let length = MockTorrent::default().total_size;
+                json!({
+                    "name": name,
+                    "info_hash": mock_hash(&path),
+                    "files_tree": {
+                        "type": "dir",
+                        "contents": {
+                            name: { "type": "file", "index": 0, "length": length }
+                        }
+                    }
+                })
+            }
+            "web.download_torrent_from_url" => {
+                let url = param_str(params, 0);
+                let file_name = url.rsplit('/').next().unwrap_or("download.torrent");
+                json!(format!("/tmp/delugeweb-mock/{file_name}"))
+            }
+            "core.add_torrent_magnet" => {
+                match self.add_torrent(&param_str(params, 0), &param(params, 1)) {
+                    Ok(id) => json!(id),
+                    Err(outcome) => return outcome,
+                }
+            }
+            "web.register_event_listener" => {
+                self.listeners.insert(param_str(params, 0));
+                Value::Null
+            }
+            "web.deregister_event_listener" => {
+                self.listeners.remove(&param_str(params, 0));
+                Value::Null
+            }
+            "web.get_events" => {
+                if self.events.is_empty() {
+                    Value::Null
+                } else {
+                    Value::Array(self.events.drain(..).collect())
+                }
+            }
+            "web.get_plugins" => json!({
+                "enabled_plugins": self.enabled_plugins,
+                "available_plugins": self.available_plugins,
+            }),
+            "core.get_session_state" => self.torrents.keys().cloned().collect(),
+            "core.get_filter_tree" => self.filter_tree(),
+            "core.get_config" => Value::Object(self.config.clone()),
+            "core.get_config_value" => self
+                .config
+                .get(&param_str(params, 0))
+                .cloned()
+                .unwrap_or_default(),
+            "core.get_config_values" => {
+                let keys = param(params, 0);
+                let values: Map<String, Value> = keys
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(|key| {
+                        let value = self.config.get(key).cloned().unwrap_or_default();
+                        (key.to_owned(), value)
+                    })
+                    .collect();
+                Value::Object(values)
+            }
+            "core.set_config" => {
+                let config = param(params, 0);
+                for (key, value) in config.as_object().into_iter().flatten() {
+                    self.config.insert(key.clone(), value.clone());
+                    self.emit_event("ConfigValueChangedEvent", json!([key, value]));
+                }
+                Value::Null
+            }
+            "core.create_torrent" => {
+                let path = param_str(params, 0);
+                let target = param(params, 4);
+                let file_name = target
+                    .as_str()
+                    .unwrap_or(&path)
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or_default()
+                    .trim_end_matches(".torrent")
+                    .to_owned();
+                if param(params, 9).as_bool() == Some(true) {
+                    let parent = path.rsplit_once('/').map(|(x, _)| x).unwrap_or_default();
+                    let options = json!({
+                        "download_location": parent,
+                        "skip_hash_check": true,
+                    });
+                    if let Err(outcome) = self.add_torrent(&path, &options) {
+                        return outcome;
+                    }
+                }
+                if self.version().starts_with("2.0.") {
+                    Value::Null
+                } else {
+                    json!([format!("{file_name}.torrent"), "ZGVsdWdl"])
+                }
+            }
+            "core.get_session_status" => {
+                let keys = param(params, 0);
+                let status: Map<String, Value> = keys
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(|key| {
+                        let value = if key.ends_with("rate") || key.ends_with("ratio") {
+                            json!(0.0)
+                        } else {
+                            json!(0)
+                        };
+                        (key.to_owned(), value)
+                    })
+                    .collect();
+                Value::Object(status)
+            }
+            "core.get_listen_port" => json!(6881),
+            "core.get_external_ip" => json!("127.0.0.1"),
+            "core.get_libtorrent_version" => json!("2.0.10.0"),
+            "daemon.info" => json!(self.version()),
+            "core.get_free_space" => json!(self.free_space),
+            "core.get_path_size" => {
+                let path = param_str(params, 0);
+                let sizes: Vec<u64> = self
+                    .torrents
+                    .values()
+                    .filter(|x| x.save_path.starts_with(&path))
+                    .map(|x| x.total_size)
+                    .collect();
+                if sizes.is_empty() {
+                    json!(-1)
+                } else {
+                    json!(sizes.iter().sum::<u64>())
+                }
+            }
+            "core.get_completion_paths" => {
+                let mut args = param(params, 0);
+                let text = args
+                    .get("completion_text")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_owned();
+                let mut paths: Vec<String> = self
+                    .torrents
+                    .values()
+                    .map(|x| x.save_path.clone())
+                    .chain(
+                        self.config
+                            .get("download_location")
+                            .and_then(Value::as_str)
+                            .map(str::to_owned),
+                    )
+                    .filter(|x| x.starts_with(&text))
+                    .map(|x| format!("{}/", x.trim_end_matches('/')))
+                    .collect();
+                paths.sort();
+                paths.dedup();
+                if let Some(args) = args.as_object_mut() {
+                    args.insert("paths".to_owned(), json!(paths));
+                }
+                args
+            }
+            "core.get_enabled_plugins" => json!(self.enabled_plugins),
+            "core.get_available_plugins" => json!(self.available_plugins),
+            "core.enable_plugin" => {
+                let name = param_str(params, 0);
+                let available = self.available_plugins.contains(&name);
+                if available && !self.enabled_plugins.contains(&name) {
+                    self.enabled_plugins.push(name.clone());
+                    self.emit_event("PluginEnabledEvent", json!([name]));
+                }
+                json!(available)
+            }
+            "core.disable_plugin" => {
+                let name = param_str(params, 0);
+                let enabled = self.enabled_plugins.contains(&name);
+                if enabled {
+                    self.enabled_plugins.retain(|x| *x != name);
+                    self.emit_event("PluginDisabledEvent", json!([name]));
+                }
+                json!(enabled)
+            }
+            "core.rescan_plugins" => Value::Null,
+            "web.get_plugin_info" => {
+                let name = param_str(params, 0);
+                if !self.available_plugins.contains(&name) {
+                    return exception(&format!("Unknown plugin {name}"));
+                }
+                json!({
+                    "Name": name,
+                    "Version": "0.1",
+                    "Author": "Deluge Team",
+                    "Author-email": "deluge@example.com",
+                    "Home-page": "https://deluge-torrent.org",
+                    "License": "GPLv3",
+                    "Description": format!("{name} plugin"),
+                })
+            }
+            "web.upload_plugin" => {
+                let file_name = param_str(params, 0);
+                let path = param_str(params, 1);
+                if !self.uploads.contains(&path) {
+                    return exception(&format!("File not found: {path}"));
+                }
+                let name = file_name.split('-').next().unwrap_or_default().to_owned();
+                if !self.available_plugins.contains(&name) {
+                    self.available_plugins.push(name);
+                }
+                json!(true)
+            }
+            "web.get_config" => Value::Object(self.web_config.clone()),
+            "web.set_config" => {
+                let config = param(params, 0);
+                for (key, value) in config.as_object().into_iter().flatten() {
+                    self.web_config.insert(key.clone(), value.clone());
+                }
+                Value::Null
+            }
+            "core.remove_torrent" => {
+                let id = param_str(params, 0);
+                if self.torrents.remove(&id).is_none() {
+                    return exception(&format!("Torrent {id} not found"));
+                }
+                self.emit_event("TorrentRemovedEvent", json!([id]));
+                json!(true)
+            }
+            "core.pause_torrent" | "core.pause_torrents" => {
+                return self.set_states(&param(params, 0), |_| State::Paused)
+            }
+            "core.resume_torrent" | "core.resume_torrents" => {
+                return self.set_states(&param(params, 0), |torrent| {
+                    if torrent.progress < 100.0 {
+                        State::Downloading
+                    } else {
+                        State::Seeding
+                    }
+                })
+            }
+            "core.move_storage" => {
+                let path = param_str(params, 1);
+                for id in ids(&param(params, 0)) {
+                    let Some(torrent) = self.torrents.get_mut(&id) else {
+                        return exception(&format!("Torrent {id} not found"));
+                    };
+                    torrent.save_path.clone_from(&path);
+                    self.emit_event("TorrentStorageMovedEvent", json!([id, path]));
+                }
+                Value::Null
+            }
+            "label.get_labels" => self.labels.keys().cloned().collect(),
+            "label.add" => {
+                let label = param_str(params, 0);
+                if self.labels.contains_key(&label) {
+                    return exception("Label already exists");
+                }
+                self.labels.insert(label, default_label_options());
+                Value::Null
+            }
+            "label.remove" => {
+                let label = param_str(params, 0);
+                if self.labels.remove(&label).is_none() {
+                    return exception("Unknown Label");
+                }
+                for torrent in self.torrents.values_mut() {
+                    if torrent.label == label {
+                        torrent.label = String::new();
+                    }
+                }
+                Value::Null
+            }
+            "label.set_torrent" => {
+                let id = param_str(params, 0);
+                let label = param_str(params, 1);
+                if !label.is_empty() && !self.labels.contains_key(&label) {
+                    return exception("Unknown Label");
+                }
+                let Some(torrent) = self.torrents.get_mut(&id) else {
+                    return exception("Unknown Torrent");
+                };
+                torrent.label = label;
+                Value::Null
+            }
+            "label.get_options" => match self.labels.get(&param_str(params, 0)) {
+                Some(options) => options.clone(),
+                None => return exception("Unknown Label"),
+            },
+            "label.set_options" => {
+                let Some(options) = self.labels.get_mut(&param_str(params, 0)) else {
+                    return exception("Unknown Label");
+                };
+                let changes = param(params, 1);
+                if let (Some(options), Some(changes)) =
+                    (options.as_object_mut(), changes.as_object())
+                {
+                    for (key, value) in changes {
+                        options.insert(key.clone(), value.clone());
+                    }
+                }
+                Value::Null
+            }
+            "label.get_config" => json!({}),
+            _ => return unknown_method(method),
+        };
+        Outcome::Result(result)
+    }
+
+    /// Handle a call to the namespace of a plugin other than Label
+    ///
+    /// Returns `None` if the method is not in one of these namespaces.
+    fn call_plugin(&mut self, method: &str, params: &[Value]) -> Option<Outcome> {
+        let (namespace, name) = method.split_once('.')?;
+        if !PLUGIN_NAMESPACES.contains(&namespace) {
+            return None;
+        }
+        let result = match (namespace, name) {
+            ("autoadd", _) => return Some(self.call_autoadd(name, params)),
+            ("execute", _) => return Some(self.call_execute(name, params)),
+            ("stats", _) => return Some(call_stats(name, params)),
+            (_, "get_config") if self.plugin_configs.contains_key(namespace) => Value::Object(
+                self.plugin_configs
+                    .get(namespace)
+                    .cloned()
+                    .unwrap_or_default(),
+            ),
+            (_, "set_config") if self.plugin_configs.contains_key(namespace) => {
+                let config = param(params, 0);
+                let current = self.plugin_configs.entry(namespace.to_owned()).or_default();
+                for (key, value) in config.as_object().into_iter().flatten() {
+                    current.insert(key.clone(), value.clone());
+                }
+                Value::Null
+            }
+            ("scheduler", "get_state") => json!("Green"),
+            ("blocklist", "check_import") => Value::Null,
+            ("blocklist", "get_status") => {
+                let config = self.plugin_configs.get("blocklist");
+                let get = |key: &str| config.and_then(|x| x.get(key)).cloned();
+                let whitelisted = get("whitelisted").unwrap_or_else(|| json!([]));
+                json!({
+                    "state": "Idle",
+                    "up_to_date": false,
+                    "num_blocked": 0,
+                    "num_whited": whitelisted.as_array().map_or(0, Vec::len),
+                    "file_progress": 0.0,
+                    "file_url": get("url").unwrap_or_else(|| json!("")),
+                    "file_size": 0,
+                    "file_date": 0.0,
+                    "file_type": "",
+                    "whitelisted": whitelisted,
+                })
+            }
+            ("blocklist", "set_whitelist") => {
+                let config = self
+                    .plugin_configs
+                    .entry("blocklist".to_owned())
+                    .or_default();
+                config.insert("whitelisted".to_owned(), param(params, 0));
+                Value::Null
+            }
+            _ => return Some(unknown_method(method)),
+        };
+        Some(Outcome::Result(result))
+    }
+
+    fn call_autoadd(&mut self, name: &str, params: &[Value]) -> Outcome {
+        let id = params
+            .first()
+            .and_then(Value::as_u64)
+            .and_then(|x| u32::try_from(x).ok())
+            .unwrap_or_default();
+        let result = match name {
+            "get_watchdirs" => {
+                let watchdirs: Map<String, Value> = self
+                    .watchdirs
+                    .iter()
+                    .map(|(id, options)| (id.to_string(), options.clone()))
+                    .collect();
+                Value::Object(watchdirs)
+            }
+            "add" => {
+                let mut options = param(params, 0);
+                let Some(path) = options.get("path").and_then(Value::as_str) else {
+                    return exception("Path is required");
+                };
+                let path = path.to_owned();
+                if let Some(options) = options.as_object_mut() {
+                    options.insert("abspath".to_owned(), json!(path));
+                    options.entry("enabled").or_insert(json!(false));
+                }
+                let id = self.watchdirs.keys().last().map_or(1, |x| x + 1);
+                self.watchdirs.insert(id, options);
+                json!(id)
+            }
+            "remove" => {
+                if self.watchdirs.remove(&id).is_none() {
+                    return exception("Watch folder does not exist");
+                }
+                Value::Null
+            }
+            "set_options" | "enable_watchdir" | "disable_watchdir" => {
+                let changes = match name {
+                    "set_options" => param(params, 1),
+                    "enable_watchdir" => json!({ "enabled": true }),
+                    _ => json!({ "enabled": false }),
+                };
+                let Some(options) = self.watchdirs.get_mut(&id) else {
+                    return exception("Watch folder does not exist");
+                };
+                if let (Some(options), Some(changes)) =
+                    (options.as_object_mut(), changes.as_object())
+                {
+                    for (key, value) in changes {
+                        options.insert(key.clone(), value.clone());
+                    }
+                }
+                Value::Null
+            }
+            _ => return unknown_method(&format!("autoadd.{name}")),
+        };
+        Outcome::Result(result)
+    }
+
+    fn call_execute(&mut self, name: &str, params: &[Value]) -> Outcome {
+        let result = match name {
+            "get_commands" => Value::Array(self.commands.clone()),
+            "add_command" => {
+                let event = param(params, 0);
+                let command = param(params, 1);
+                let id = mock_hash(&format!("{}{event}{command}", self.calls.len()));
+                self.commands.push(json!([id, event, command]));
+                Value::Null
+            }
+            "remove_command" => {
+                let id = param(params, 0);
+                self.commands.retain(|x| x.get(0) != Some(&id));
+                Value::Null
+            }
+            "save_command" => {
+                let id = param(params, 0);
+                for command in &mut self.commands {
+                    if command.get(0) == Some(&id) {
+                        *command = json!([id, param(params, 1), param(params, 2)]);
+                    }
+                }
+                Value::Null
+            }
+            _ => return unknown_method(&format!("execute.{name}")),
+        };
+        Outcome::Result(result)
+    }
+
+    /// Version of the connected host
+    fn version(&self) -> &str {
+        self.hosts
+            .iter()
+            .find(|x| Some(&x.id) == self.connected.as_ref())
+            .map_or(DEFAULT_VERSION, |x| x.version.as_str())
+    }
+
+    fn update_ui(&self, filters: &Value) -> Value {
+        let torrents: Map<String, Value> = self
+            .torrents
+            .iter()
+            .filter(|(id, torrent)| matches_filters(id, torrent, filters))
+            .map(|(id, torrent)| (id.clone(), torrent.status()))
+            .collect();
+        json!({
+            "connected": self.connected.is_some(),
+            "filters": self.filter_tree(),
+            "stats": {
+                "dht_nodes": 0,
+                "download_protocol_rate": 0.0,
+                "download_rate": 0.0,
+                "external_ip": "127.0.0.1",
+                "free_space": self.free_space.max(0),
+                "has_incoming_connections": 1,
+                "max_download": -1.0,
+                "max_num_connections": 200,
+                "max_upload": -1.0,
+                "num_connections": 0,
+                "upload_protocol_rate": 0.0,
+                "upload_rate": 0.0,
+            },
+            "torrents": torrents,
+        })
+    }
+
+    /// Count the torrents for each value of each category with `All` first
+    fn filter_tree(&self) -> Value {
+        let mut tree = Map::new();
+        let categories: [Category; 4] = [
+            ("state", |x| state_name(x.state)),
+            ("tracker_host", |x| x.tracker_host.clone()),
+            ("owner", |x| x.owner.clone()),
+            ("label", |x| x.label.clone()),
+        ];
+        for (category, get) in categories {
+            if category == "label" && !self.enabled_plugins.iter().any(|x| x == "Label") {
+                continue;
+            }
+            let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+            for torrent in self.torrents.values() {
+                *counts.entry(get(torrent)).or_default() += 1;
+            }
+            let mut values = vec![json!(["All", self.torrents.len()])];
+            values.extend(
+                counts
+                    .into_iter()
+                    .map(|(value, count)| json!([value, count])),
+            );
+            tree.insert(category.to_owned(), Value::Array(values));
+        }
+        Value::Object(tree)
+    }
+
+    fn add_torrents(&mut self, torrents: &Value) -> Outcome {
+        let mut added = Vec::new();
+        for torrent in torrents.as_array().into_iter().flatten() {
+            let path = torrent
+                .get("path")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let options = torrent.get("options").cloned().unwrap_or_default();
+            match self.add_torrent(path, &options) {
+                Ok(id) => added.push(json!([true, id])),
+                Err(outcome) => return outcome,
+            }
+        }
+        Outcome::Result(Value::Array(added))
+    }
+
+    /// Add a torrent by path or magnet URI and get its id
+    fn add_torrent(&mut self, source: &str, options: &Value) -> Result<String, Outcome> {
+        let id = mock_hash(source);
+        if self.torrents.contains_key(&id) {
+            if self.hang_on_duplicate {
+                return Err(Outcome::Hang);
+            }
+            return Err(exception("Torrent already in session"));
+        }
+        let name = match source.split_once("dn=") {
+            Some((_, name)) => name.split('&').next().unwrap_or(name),
+            None => source
+                .rsplit('/')
+                .next()
+                .unwrap_or(source)
+                .trim_end_matches(".torrent"),
+        }
+        .to_owned();
+        let paused = options.get("add_paused").and_then(Value::as_bool) == Some(true);
+        let skip_check = options.get("skip_hash_check").and_then(Value::as_bool) == Some(true);
+        let save_path = options
+            .get("download_location")
+            .and_then(Value::as_str)
+            .map(str::to_owned)
+            .or_else(|| {
+                self.config
+                    .get("download_location")
+                    .and_then(Value::as_str)
+                    .map(str::to_owned)
+            })
+            .unwrap_or_default();
+        let state = match (paused, skip_check) {
+            (true, _) => State::Paused,
+            (false, true) => State::Seeding,
+            (false, false) => State::Downloading,
+        };
+        let mock = MockTorrent {
+            name,
+            state,
+            save_path,
+            progress: if skip_check { 100.0 } else { 0.0 },
+            ..MockTorrent::default()
+        };
+        self.torrents.insert(id.clone(), mock);
+        self.emit_event("TorrentAddedEvent", json!([id, false]));
+        Ok(id)
+    }
+
+    fn set_states(&mut self, ids_param: &Value, get_state: fn(&MockTorrent) -> State) -> Outcome {
+        for id in ids(ids_param) {
+            let Some(torrent) = self.torrents.get_mut(&id) else {
+                return exception(&format!("Torrent {id} not found"));
+            };
+            torrent.state = get_state(torrent);
+            let state = torrent.state;
+            self.emit_event("TorrentStateChangedEvent", json!([id, state]));
+        }
+        Outcome::Result(Value::Null)
+    }
+}
+
+/// Filter tree category and how to get its value from a torrent
+type Category = (&'static str, fn(&MockTorrent) -> String);
+
+enum Outcome {
+    Result(Value),
+    Error(Value),
+    /// Never respond
+    Hang,
+}
+
+struct Reply {
+    status: StatusCode,
+    body: Value,
+    cookie: Option<String>,
+    hang: bool,
+}
+
+impl Default for Reply {
+    fn default() -> Self {
+        Self {
+            status: StatusCode::OK,
+            body: Value::Null,
+            cookie: None,
+            hang: false,
+        }
+    }
+}
+
+async fn serve(listener: TcpListener, state: Arc<Mutex<MockState>>) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let state = state.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request| handle(request, state.clone()));
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+async fn handle(
+    request: Request<Incoming>,
+    state: Arc<Mutex<MockState>>,
+) -> Result<hyper::Response<Full<Bytes>>, hyper::Error> {
+    let session = request
+        .headers()
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|x| x.to_str().ok())
+        .flat_map(|x| x.split(';'))
+        .filter_map(|x| x.trim().strip_prefix(&format!("{SESSION_COOKIE}=")))
+        .map(str::to_owned)
+        .next();
+    let path = request.uri().path().to_owned();
+    let body = request.into_body().collect().await?.to_bytes();
+    let latency = state.lock().expect("lock should not be poisoned").latency;
+    if !latency.is_zero() {
+        sleep(latency).await;
+    }
+    let reply = {
+        let mut state = state.lock().expect("lock should not be poisoned");
+        match path.as_str() {
+            "/json" => {
+                let request: Value = serde_json::from_slice(&body).unwrap_or_default();
+                state.handle_json(&request, session)
+            }
+            "/upload" => state.handle_upload(&body),
+            _ => Reply {
+                status: StatusCode::NOT_FOUND,
+                ..Reply::default()
+            },
+        }
+    };
+    if reply.hang {
+        pending::<()>().await;
+    }
+    let mut response = hyper::Response::builder()
+        .status(reply.status)
+        .header(CONTENT_TYPE, "application/json");
+    if let Some(cookie) = reply.cookie {
+        response = response.header(SET_COOKIE, cookie);
+    }
+    let response = response
+        .body(Full::new(Bytes::from(reply.body.to_string())))
+        .expect("response should be valid");
+    Ok(response)
+}
+
+fn matches_filters(id: &str, torrent: &MockTorrent, filters: &Value) -> bool {
+    let Some(filters) = filters.as_object() else {
+        return true;
+    };
+    filters.iter().all(|(key, values)| {
+        let actual = match key.as_str() {
+            "id" => id.to_owned(),
+            "name" => torrent.name.clone(),
+            "state" => state_name(torrent.state),
+            "label" => torrent.label.clone(),
+            "owner" => torrent.owner.clone(),
+            "tracker_host" => torrent.tracker_host.clone(),
+            _ => return true,
+        };
+        let values: Vec<&str> = match values {
+            Value::String(value) => vec![value.as_str()],
+            Value::Array(values) => values.iter().filter_map(Value::as_str).collect(),
+            _ => return true,
+        };
+        values.iter().any(|x| *x == "All" || *x == actual)
+    })
+}
+
+/// Name of a state as sent by Deluge
+fn state_name(state: State) -> String {
+    json!(state).as_str().unwrap_or_default().to_owned()
+}
+
+fn default_web_config() -> Map<String, Value> {
+    let config = json!({
+        "port": 8112,
+        "interface": "0.0.0.0",
+        "base": "/",
+        "https": false,
+        "pkey": "ssl/daemon.pkey",
+        "cert": "ssl/daemon.cert",
+        "session_timeout": 3600,
+        "theme": "gray",
+        "language": "",
+        "show_sidebar": true,
+        "sidebar_show_zero": false,
+        "sidebar_multiple_filters": true,
+        "show_session_speed": false,
+        "default_daemon": "",
+        "first_login": true,
+        "enabled_plugins": [],
+    });
+    config.as_object().cloned().unwrap_or_default()
+}
+
+fn default_plugin_configs() -> BTreeMap<String, Map<String, Value>> {
+    let configs = json!({
+        "blocklist": {
+            "url": "",
+            "load_on_start": false,
+            "check_after_days": 4,
+            "list_compression": "",
+            "list_type": "",
+            "last_update": 0.0,
+            "list_size": 0,
+            "timeout": 180,
+            "try_times": 3,
+            "whitelisted": [],
+        },
+        "extractor": {
+            "extract_path": "",
+            "use_name_folder": true,
+        },
+        "notifications": {
+            "smtp_enabled": false,
+            "smtp_host": "",
+            "smtp_port": 25,
+            "smtp_user": "",
+            "smtp_pass": "",
+            "smtp_from": "",
+            "smtp_tls": false,
+            "smtp_recipients": [],
+            "subscriptions": { "email": [] },
+        },
+        "scheduler": {
+            "low_down": -1.0,
+            "low_up": -1.0,
+            "low_active": -1,
+            "low_active_down": -1,
+            "low_active_up": -1,
+            "button_state": vec![vec![0; 7]; 24],
+        },
+    });
+    configs
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(namespace, config)| {
+            let config = config.as_object().cloned().unwrap_or_default();
+            (namespace.clone(), config)
+        })
+        .collect()
+}
+
+fn default_label_options() -> Value {
+    json!({
+        "apply_max": false,
+        "max_download_speed": -1.0,
+        "max_upload_speed": -1.0,
+        "max_connections": -1,
+        "max_upload_slots": -1,
+        "prioritize_first_last": false,
+        "apply_queue": false,
+        "is_auto_managed": false,
+        "stop_at_ratio": false,
+        "stop_ratio": 2.0,
+        "remove_at_ratio": false,
+        "apply_move_completed": false,
+        "move_completed": false,
+        "move_completed_path": "",
+        "auto_add": false,
+        "auto_add_trackers": [],
+    })
+}
+
+/// Derive a stable 40 character hex id from a torrent path
+fn mock_hash(path: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut id = String::new();
+    while id.len() < 40 {
+        for byte in path.bytes() {
+            hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+        let _ = write!(id, "{hash:016x}");
+    }
+    id.truncate(40);
+    id
+}
+
+fn ids(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(id) => vec![id.clone()],
+        Value::Array(ids) => ids
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_owned)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn param(params: &[Value], index: usize) -> Value {
+    params.get(index).cloned().unwrap_or_default()
+}
+
+fn param_str(params: &[Value], index: usize) -> String {
+    params
+        .get(index)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_owned()
+}
+
+fn error(code: u64, message: &str) -> Value {
+    json!({ "message": message, "code": code })
+}
+
+/// Handle a call to the Stats plugin, which records no samples in the mock
+fn call_stats(name: &str, params: &[Value]) -> Outcome {
+    let result = match name {
+        "get_intervals" => json!(STATS_INTERVALS),
+        "get_stats" => {
+            let interval = param(params, 1);
+            if !STATS_INTERVALS
+                .iter()
+                .any(|x| Some(*x) == interval.as_u64())
+            {
+                return exception(&format!("Unknown interval {interval}"));
+            }
+            let mut stats: Map<String, Value> = param(params, 0)
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(|key| (key.to_owned(), json!([])))
+                .collect();
+            stats.insert("_last_update".to_owned(), json!(0.0));
+            stats.insert("_length".to_owned(), json!(150));
+            stats.insert("_update_interval".to_owned(), interval);
+            Value::Object(stats)
+        }
+        "get_totals" | "get_session_totals" => json!({
+            "total_upload": 0,
+            "total_download": 0,
+            "total_payload_upload": 0,
+            "total_payload_download": 0,
+        }),
+        _ => return unknown_method(&format!("stats.{name}")),
+    };
+    Outcome::Result(result)
+}
+
+fn unknown_method(method: &str) -> Outcome {
+    Outcome::Error(error(
+        ERROR_UNKNOWN_METHOD,
+        &format!("Unknown method {method}"),
+    ))
+}
+
+fn exception(message: &str) -> Outcome {
+    Outcome::Error(error(ERROR_EXCEPTION, message))
+}
+
+fn start_error(message: String) -> Error {
+    Error {
+        action: "start mock server".to_owned(),
+        domain: Some("mock server".to_owned()),
+        message,
+        ..Error::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::add_torrents::{Options, TorrentPath};
+    use crate::events::DelugeEvent;
+    use crate::get_torrents::FilterOptions;
+    use crate::label::LabelOptions;
+    use crate::scheduler::{SchedulerConfig, Slot};
+    use crate::web_config::WebConfig;
+    use tokio::time::timeout;
+
+    async fn start() -> Result<MockServer, Error> {
+        let server = MockServer::start().await?;
+        {
+            let mut state = server.state();
+            state
+                .labels
+                .insert("linux".to_owned(), default_label_options());
+            state.torrents.insert(
+                "a".repeat(40),
+                MockTorrent {
+                    name: "debian".to_owned(),
+                    label: "linux".to_owned(),
+                    ..MockTorrent::default()
+                },
+            );
+            state.torrents.insert(
+                "b".repeat(40),
+                MockTorrent {
+                    name: "windows".to_owned(),
+                    state: State::Downloading,
+                    progress: 50.0,
+                    ..MockTorrent::default()
+                },
+            );
+        }
+        Ok(server)
+    }
+
+    #[tokio::test]
+    async fn mock_requires_login() -> Result<(), Error> {
+        // Arrange
+        let server = start().await?;
+        let mut client = server.client();
+
+        // Act
+        let before = client.get_hosts().await?;
+        let login = client.login().await?;
+        let after = client.get_hosts().await?;
+
+        // Assert
+        let error = before
+            .get_result("get_hosts")
+            .expect_err("should require login");
+        assert!(error.message.contains("Not authenticated"));
+        assert!(login.get_result("login")?);
+        assert_eq!(after.get_result("get_hosts")?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mock_wrong_password() -> Result<(), Error> {
+        // Arrange
+        let server = start().await?;
+        let mut options = server.options();
+        options.password = "wrong".to_owned();
+        let mut client = DelugeClientFactory { options }.create();
+
+        // Act
+        let login = client.login().await?;
+
+        // Assert
+        assert!(!login.get_result("login")?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mock_torrents() -> Result<(), Error> {
+        // Arrange
+        let server = start().await?;
+        let mut client = server.client();
+        let filters = FilterOptions {
+            label: Some(vec!["linux".to_owned()]),
+            ..FilterOptions::default()
+        };
+
+        // Act
+        client.login().await?;
+        let torrents = client.get_torrents(filters).await?;
+        let interface = client.get_interface(FilterOptions::default()).await?;
+        let status = client.get_torrent_status(&"b".repeat(40)).await?;
+
+        // Assert
+        let torrents = torrents.get_result("get_torrents")?;
+        assert_eq!(torrents.len(), 1);
+        let interface = interface.get_result("get_interface")?;
+        let state = interface.filters.state.expect("should have state");
+        assert_eq!(state.get("All"), Some(2));
+        assert_eq!(state.get("Downloading"), Some(1));
+        let status = status.get_result("get_torrent_status")?;
+        assert_eq!(status.name, "windows");
+        assert_eq!(status.total_remaining, 524_288);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mock_labels() -> Result<(), Error> {
+        // Arrange
+        let server = start().await?;
+        let mut client = server.client();
+        let id = "b".repeat(40);
+        let options = LabelOptions {
+            max_upload_speed: Some(100.0),
+            ..LabelOptions::default()
+        };
+
+        // Act
+        client.login().await?;
+        client.add_label("windows").await?.check("add_label")?;
+        let duplicate = client.add_label("windows").await?;
+        client
+            .set_torrent_label(&id, "windows")
+            .await?
+            .check("set_torrent_label")?;
+        client
+            .set_label_options("windows", options)
+            .await?
+            .check("set_label_options")?;
+        let labels = client.get_labels().await?;
+        let options = client.get_label_options("windows").await?;
+
+        // Assert
+        assert!(duplicate.check("add_label").is_err());
+        assert_eq!(labels.get_result("get_labels")?, vec!["linux", "windows"]);
+        let options = options.get_result("get_label_options")?;
+        assert_eq!(options.max_upload_speed, Some(100.0));
+        let torrent = server.state().torrents.get(&id).cloned();
+        assert_eq!(torrent.map(|x| x.label).as_deref(), Some("windows"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mock_config_and_events() -> Result<(), Error> {
+        // Arrange
+        let server = start().await?;
+        let mut client = server.client();
+        let mut config = Map::new();
+        config.insert("max_active_seeding".to_owned(), json!(10));
+
+        // Act
+        client.login().await?;
+        client
+            .register_event_listener("ConfigValueChangedEvent")
+            .await?
+            .check("register_event_listener")?;
+        let _: crate::Response<()> = client.call("core.set_config", (config,)).await?;
+        let value: crate::Response<u32> = client
+            .call("core.get_config_value", ("max_active_seeding",))
+            .await?;
+        let events = client.get_events().await?;
+
+        // Assert
+        assert_eq!(value.get_result("get_config_value")?, 10);
+        assert_eq!(
+            events.get_result("get_events")?,
+            vec![DelugeEvent::ConfigValueChanged {
+                key: "max_active_seeding".to_owned(),
+                value: json!(10)
+            }]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mock_plugins() -> Result<(), Error> {
+        // Arrange
+        let server = start().await?;
+        let mut client = server.client();
+        let web_config = WebConfig {
+            theme: Some("dark".to_owned()),
+            ..WebConfig::default()
+        };
+
+        // Act
+        client.login().await?;
+        let enabled = client.enable_plugin("Scheduler").await?;
+        let info = client.get_plugin_info("Scheduler").await?;
+        let unknown = client.get_plugin_info("Missing").await?;
+        let mut config = client
+            .get_scheduler_config()
+            .await?
+            .get_result("get_scheduler_config")?;
+        config.button_state.set(6, 23, Slot::Red);
+        client
+            .set_scheduler_config(config)
+            .await?
+            .check("set_scheduler_config")?;
+        let config: SchedulerConfig = client
+            .get_scheduler_config()
+            .await?
+            .get_result("get_scheduler_config")?;
+        client
+            .set_web_config(web_config)
+            .await?
+            .check("set_web_config")?;
+        let web_config = client.get_web_config().await?;
+        let totals = client.get_stats_totals().await?;
+        let missing: crate::Response<()> = client.call("scheduler.missing", ()).await?;
+
+        // Assert
+        assert!(enabled.get_result("enable_plugin")?);
+        assert!(server
+            .state()
+            .enabled_plugins
+            .contains(&"Scheduler".to_owned()));
+        assert_eq!(info.get_result("get_plugin_info")?.name, "Scheduler");
+        assert!(unknown.get_result("get_plugin_info").is_err());
+        assert_eq!(config.button_state.get(6, 23), Some(Slot::Red));
+        let web_config = web_config.get_result("get_web_config")?;
+        assert_eq!(web_config.theme.as_deref(), Some("dark"));
+        assert_eq!(web_config.port, Some(8112));
+        assert_eq!(totals.get_result("get_stats_totals")?.total_upload, 0);
+        let error = missing.check("missing").expect_err("should be unknown");
+        assert!(error.message.contains("Unknown method"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mock_injected_error_and_latency() -> Result<(), Error> {
+        // Arrange
+        let server = start().await?;
+        let mut client = server.client();
+        {
+            let mut state = server.state();
+            state.inject_error("label.get_labels", "Plugin is disabled");
+            state.latency = Duration::from_millis(50);
+        }
+
+        // Act
+        client.login().await?;
+        let response = client.get_labels().await;
+        let slow = timeout(Duration::from_millis(10), client.get_hosts()).await;
+
+        // Assert
+        let error = response?
+            .check("get_labels")
+            .expect_err("should be injected");
+        assert!(error.message.contains("Plugin is disabled"));
+        assert!(slow.is_err(), "latency should delay the response");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn mock_add_duplicate_torrent_hangs() -> Result<(), Error> {
+        // Arrange
+        let server = start().await?;
+        let mut client = server.client();
+        let torrent = || TorrentPath {
+            path: "/srv/shared/tests/example-1.torrent".to_owned(),
+            options: Options {
+                skip_hash_check: Some(true),
+                ..Options::default()
+            },
+        };
+
+        // Act
+        client.login().await?;
+        let added = client.add_torrents(vec![torrent()]).await?;
+        let duplicate = timeout(
+            Duration::from_millis(200),
+            client.add_torrents(vec![torrent()]),
+        )
+        .await;
+
+        // Assert
+        assert_eq!(added.get_result("add_torrents")?.len(), 1);
+        assert!(duplicate.is_err(), "duplicate should hang");
+        assert_eq!(server.state().torrents.len(), 3);
+        Ok(())
+    }
+}
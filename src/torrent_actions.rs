@@ -0,0 +1,100 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+
+impl DelugeClient {
+    /// Remove a torrent from the session.
+    ///
+    /// If `remove_data` is true the downloaded files are also deleted.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn remove_torrent(
+        &mut self,
+        torrent_id: &str,
+        remove_data: bool,
+    ) -> Result<Response<bool>, Error> {
+        self.call("core.remove_torrent", (torrent_id, remove_data))
+            .await
+    }
+
+    /// Pause torrents.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn pause_torrents(
+        &mut self,
+        torrent_ids: Vec<String>,
+    ) -> Result<Response<()>, Error> {
+        self.call("core.pause_torrents", (torrent_ids,)).await
+    }
+
+    /// Resume torrents.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn resume_torrents(
+        &mut self,
+        torrent_ids: Vec<String>,
+    ) -> Result<Response<()>, Error> {
+        self.call("core.resume_torrents", (torrent_ids,)).await
+    }
+
+    /// Move the files of torrents to another directory.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/api.html>
+    pub async fn move_storage(
+        &mut self,
+        torrent_ids: Vec<String>,
+        destination: &str,
+    ) -> Result<Response<()>, Error> {
+        self.call("core.move_storage", (torrent_ids, destination))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{MockServer, MockTorrent};
+    use crate::State;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn torrent_actions() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let id = "a".repeat(40);
+        server
+            .state()
+            .torrents
+            .insert(id.clone(), MockTorrent::default());
+        let mut client = server.client();
+
+        // Act
+        client.login().await?;
+        client
+            .pause_torrents(vec![id.clone()])
+            .await?
+            .check("pause_torrents")?;
+        let paused = server.state().torrents.get(&id).map(|x| x.state);
+        client
+            .resume_torrents(vec![id.clone()])
+            .await?
+            .check("resume_torrents")?;
+        client
+            .move_storage(vec![id.clone()], "/archive")
+            .await?
+            .check("move_storage")?;
+        let moved = server.state().torrents.get(&id).cloned();
+        let removed = client.remove_torrent(&id, false).await?;
+
+        // Assert
+        assert_eq!(paused, Some(State::Paused));
+        let moved = moved.expect("torrent should exist");
+        assert_eq!(moved.state, State::Seeding);
+        assert_eq!(moved.save_path, "/archive");
+        assert!(removed.get_result("remove_torrent")?);
+        assert!(server.state().torrents.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+use crate::Response;
+use futures::future::BoxFuture;
+use log::*;
+use rogue_logging::Error;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+/// Send calls to Deluge and receive untyped responses
+///
+/// Every API method of [`DelugeClient`](crate::DelugeClient) is written against
+/// this trait so a custom implementation, for example an in-memory fake for
+/// tests, gets every wrapper for free.
+///
+/// Implementations:
+/// - [`WebTransport`](crate::WebTransport) JSON-RPC over HTTP to deluge-web
+/// - [`DaemonTransport`](crate::DaemonTransport) native RPC over TLS to the daemon
+pub trait Transport: Send {
+    /// Send a call and receive its response
+    ///
+    /// `params` is a JSON array. The response id is validated by the caller.
+    fn send<'a>(
+        &'a mut self,
+        id: u32,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<Response<Value>, Error>>;
+
+    /// Send a call and receive the body of its response before it is parsed
+    ///
+    /// Used by [`RecordingTransport`](crate::RecordingTransport) so responses
+    /// that fail to parse can be replayed. The default implementation
+    /// serializes the response of [`Transport::send`].
+    fn send_raw<'a>(
+        &'a mut self,
+        id: u32,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<RawResponse, Error>> {
+        Box::pin(async move {
+            let response = self.send(id, method, params).await?;
+            Ok(RawResponse::from_response(&response))
+        })
+    }
+
+    /// Send several calls and receive their responses in the same order
+    ///
+    /// The default implementation sends them one after another.
+    fn send_batch(
+        &mut self,
+        requests: Vec<TransportRequest>,
+    ) -> BoxFuture<'_, Vec<Result<Response<Value>, Error>>> {
+        Box::pin(async move {
+            let mut responses = Vec::new();
+            for request in requests {
+                let response = self.send(request.id, &request.method, request.params);
+                responses.push(response.await);
+            }
+            responses
+        })
+    }
+
+    /// Send several calls and receive the bodies of their responses in the
+    /// same order
+    ///
+    /// The default implementation sends them one after another with
+    /// [`Transport::send_raw`].
+    fn send_batch_raw(
+        &mut self,
+        requests: Vec<TransportRequest>,
+    ) -> BoxFuture<'_, Vec<Result<RawResponse, Error>>> {
+        Box::pin(async move {
+            let mut responses = Vec::new();
+            for request in requests {
+                let response = self.send_raw(request.id, &request.method, request.params);
+                responses.push(response.await);
+            }
+            responses
+        })
+    }
+
+    /// Upload a file and get its server side path
+    ///
+    /// The default implementation returns an error as only deluge-web accepts
+    /// uploads.
+    fn upload(
+        &mut self,
+        file_name: String,
+        _bytes: Vec<u8>,
+    ) -> BoxFuture<'_, Result<String, Error>> {
+        Box::pin(async move {
+            Err(Error {
+                action: format!("upload {file_name}"),
+                domain: Some("Deluge API".to_owned()),
+                message: "Uploads are not supported by this transport".to_owned(),
+                ..Error::default()
+            })
+        })
+    }
+}
+
+/// A call sent by [`Transport::send_batch`]
+#[derive(Clone, Debug)]
+pub struct TransportRequest {
+    pub id: u32,
+    pub method: String,
+    pub params: Value,
+}
+
+/// Body of a response before it is parsed
+#[derive(Clone, Debug)]
+pub struct RawResponse {
+    /// HTTP status code of the response
+    pub status_code: Option<u16>,
+    /// JSON-RPC response as received
+    pub body: String,
+}
+
+impl RawResponse {
+    /// Serialize a response that was not received as JSON
+    #[must_use]
+    pub fn from_response(response: &Response<Value>) -> Self {
+        let body = json!({
+            "result": response.result,
+            "error": response.error,
+            "id": response.id,
+        });
+        Self {
+            status_code: response.status_code,
+            body: body.to_string(),
+        }
+    }
+
+    /// Parse the body as the response of a `method` call
+    pub fn parse<T: DeserializeOwned>(&self, method: &str) -> Result<Response<T>, Error> {
+        match serde_json::from_str::<Response<T>>(&self.body) {
+            Ok(mut response) => {
+                response.status_code = self.status_code;
+                Ok(response)
+            }
+            Err(e) => {
+                trace!("{}", self.body);
+                Err(Error {
+                    action: format!("deserialize response of Deluge API {method} request"),
+                    domain: Some("deserialization".to_owned()),
+                    message: e.to_string(),
+                    status_code: self.status_code,
+                    ..Error::default()
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DelugeClient, DelugeClientFactory, DelugeClientOptions, ID_MISMATCH};
+    use serde_json::json;
+
+    /// Respond to every call with its method and params
+    struct EchoTransport {
+        id_offset: u32,
+    }
+
+    impl Transport for EchoTransport {
+        fn send<'a>(
+            &'a mut self,
+            id: u32,
+            method: &'a str,
+            params: Value,
+        ) -> BoxFuture<'a, Result<Response<Value>, Error>> {
+            Box::pin(async move {
+                Ok(Response {
+                    status_code: Some(200),
+                    result: Some(json!({ "method": method, "params": params })),
+                    error: None,
+                    id: usize::try_from(id + self.id_offset).ok(),
+                })
+            })
+        }
+    }
+
+    fn create_client(id_offset: u32) -> DelugeClient {
+        let factory = DelugeClientFactory {
+            options: DelugeClientOptions::default(),
+        };
+        factory.create_with_transport(EchoTransport { id_offset })
+    }
+
+    #[tokio::test]
+    async fn custom_transport() -> Result<(), Error> {
+        // Arrange
+        let mut client = create_client(0);
+
+        // Act
+        let login = client.login().await;
+        let echo: Response<Value> = client.call("label.get_options", ("linux",)).await?;
+
+        // Assert
+        assert!(login.is_err(), "echo should not deserialize as bool");
+        assert_eq!(echo.id, Some(2));
+        assert_eq!(
+            echo.get_result("echo")?,
+            json!({ "method": "label.get_options", "params": ["linux"] })
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn custom_transport_id_mismatch() {
+        // Arrange
+        let mut client = create_client(1);
+
+        // Act
+        let result: Result<Response<Value>, Error> =
+            client.call("core.get_session_state", ()).await;
+
+        // Assert
+        let error = result.expect_err("should be a mismatch");
+        assert_eq!(error.domain.as_deref(), Some(ID_MISMATCH));
+    }
+}
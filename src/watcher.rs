@@ -0,0 +1,372 @@
+use crate::get_torrents::{FilterOptions, Torrent};
+use crate::{DelugeClient, State};
+use futures::stream::{unfold, Stream};
+use rogue_logging::Error;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tokio::time::sleep;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+const DEFAULT_MILESTONES: [f64; 4] = [25.0, 50.0, 75.0, 100.0];
+
+/// Watch for torrent changes by polling [`DelugeClient::get_torrents`]
+///
+/// Each poll is compared to the previous one to detect changes. Use this when
+/// event listeners are not reliable, otherwise prefer [`DelugeClient::events`].
+#[derive(Clone, Debug)]
+pub struct TorrentWatcher {
+    /// Filter the torrents to watch
+    pub filters: FilterOptions,
+    /// Duration between polls
+    pub interval: Duration,
+    /// Progress percentages that emit [`TorrentChange::ProgressMilestone`]
+    pub milestones: Vec<f64>,
+    /// Emit [`TorrentChange::Added`] for the torrents of the first poll
+    ///
+    /// Otherwise the first poll is only used as the baseline.
+    pub emit_initial: bool,
+}
+
+impl Default for TorrentWatcher {
+    fn default() -> Self {
+        Self {
+            filters: FilterOptions::default(),
+            interval: DEFAULT_INTERVAL,
+            milestones: DEFAULT_MILESTONES.to_vec(),
+            emit_initial: false,
+        }
+    }
+}
+
+impl TorrentWatcher {
+    /// Poll for changes until the stream is dropped.
+    ///
+    /// A failed poll is returned as an error and polling continues at the next
+    /// interval.
+    pub fn watch(
+        self,
+        client: &mut DelugeClient,
+    ) -> impl Stream<Item = Result<TorrentChange, Error>> + '_ {
+        let state = WatchState {
+            client,
+            watcher: self,
+            polled: false,
+            previous: None,
+            queue: VecDeque::new(),
+        };
+        unfold(state, |mut state| async move {
+            loop {
+                if let Some(change) = state.queue.pop_front() {
+                    return Some((Ok(change), state));
+                }
+                if state.polled {
+                    sleep(state.watcher.interval).await;
+                }
+                state.polled = true;
+                let filters = state.watcher.filters.clone();
+                let result = state
+                    .client
+                    .get_torrents(filters)
+                    .await
+                    .and_then(|response| response.get_result("get torrents"));
+                match result {
+                    Ok(current) => {
+                        let mut changes = match &state.previous {
+                            Some(previous) => diff(previous, &current, &state.watcher.milestones),
+                            None if state.watcher.emit_initial => {
+                                diff(&HashMap::new(), &current, &state.watcher.milestones)
+                            }
+                            None => Vec::new(),
+                        };
+                        if let Err(error) = find_left_filter(state.client, &mut changes).await {
+                            return Some((Err(error), state));
+                        }
+                        state.queue.extend(changes);
+                        state.previous = Some(current);
+                    }
+                    Err(error) => return Some((Err(error), state)),
+                }
+            }
+        })
+    }
+}
+
+struct WatchState<'a> {
+    client: &'a mut DelugeClient,
+    watcher: TorrentWatcher,
+    polled: bool,
+    previous: Option<HashMap<String, Torrent>>,
+    queue: VecDeque<TorrentChange>,
+}
+
+/// A change detected by a [`TorrentWatcher`]
+#[derive(Clone, Debug, Serialize)]
+pub enum TorrentChange {
+    /// Torrent was added or started matching the filters
+    Added {
+        id: String,
+        torrent: Torrent,
+    },
+    /// Torrent was removed from the session
+    Removed {
+        id: String,
+        torrent: Torrent,
+    },
+    /// Torrent stopped matching the filters but is still in the session
+    ///
+    /// For example a state change under a `state` filter.
+    LeftFilter {
+        id: String,
+        torrent: Torrent,
+    },
+    StateChanged {
+        id: String,
+        from: State,
+        to: State,
+    },
+    /// Progress reached or passed a milestone percentage
+    ProgressMilestone {
+        id: String,
+        milestone: f64,
+    },
+    LabelChanged {
+        id: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl TorrentChange {
+    /// Id of the torrent that changed
+    #[must_use]
+    pub fn id(&self) -> &str {
+        match self {
+            TorrentChange::Added { id, .. }
+            | TorrentChange::Removed { id, .. }
+            | TorrentChange::LeftFilter { id, .. }
+            | TorrentChange::StateChanged { id, .. }
+            | TorrentChange::ProgressMilestone { id, .. }
+            | TorrentChange::LabelChanged { id, .. } => id,
+        }
+    }
+
+    /// Is this a torrent finishing downloading and starting to seed
+    #[must_use]
+    pub fn is_completion(&self) -> bool {
+        matches!(
+            self,
+            TorrentChange::StateChanged {
+                from: State::Downloading,
+                to: State::Seeding,
+                ..
+            }
+        )
+    }
+}
+
+/// Replace [`TorrentChange::Removed`] with [`TorrentChange::LeftFilter`] for
+/// torrents that are still in the session
+async fn find_left_filter(
+    client: &mut DelugeClient,
+    changes: &mut [TorrentChange],
+) -> Result<(), Error> {
+    let ids: Vec<String> = changes
+        .iter()
+        .filter(|change| matches!(change, TorrentChange::Removed { .. }))
+        .map(|change| change.id().to_owned())
+        .collect();
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let filters = FilterOptions {
+        id: Some(ids),
+        ..FilterOptions::default()
+    };
+    let mut remaining = client
+        .get_torrents(filters)
+        .await?
+        .get_result("get torrents")?;
+    for change in changes.iter_mut() {
+        let id = change.id().to_owned();
+        if !matches!(change, TorrentChange::Removed { .. }) {
+            continue;
+        }
+        if let Some(torrent) = remaining.remove(&id) {
+            *change = TorrentChange::LeftFilter { id, torrent };
+        }
+    }
+    Ok(())
+}
+
+/// Compare two snapshots and get the changes ordered by torrent id
+pub(crate) fn diff(
+    previous: &HashMap<String, Torrent>,
+    current: &HashMap<String, Torrent>,
+    milestones: &[f64],
+) -> Vec<TorrentChange> {
+    let mut changes = Vec::new();
+    let mut ids: Vec<&String> = previous.keys().chain(current.keys()).collect();
+    ids.sort();
+    ids.dedup();
+    for id in ids {
+        match (previous.get(id), current.get(id)) {
+            (None, Some(torrent)) => changes.push(TorrentChange::Added {
+                id: id.clone(),
+                torrent: torrent.clone(),
+            }),
+            (Some(torrent), None) => changes.push(TorrentChange::Removed {
+                id: id.clone(),
+                torrent: torrent.clone(),
+            }),
+            (Some(before), Some(after)) => {
+                if before.state != after.state {
+                    changes.push(TorrentChange::StateChanged {
+                        id: id.clone(),
+                        from: before.state,
+                        to: after.state,
+                    });
+                }
+                for milestone in milestones {
+                    if before.progress < *milestone && after.progress >= *milestone {
+                        changes.push(TorrentChange::ProgressMilestone {
+                            id: id.clone(),
+                            milestone: *milestone,
+                        });
+                    }
+                }
+                if before.label != after.label {
+                    changes.push(TorrentChange::LabelChanged {
+                        id: id.clone(),
+                        from: before.label.clone(),
+                        to: after.label.clone(),
+                    });
+                }
+            }
+            (None, None) => {}
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockServer, MockTorrent};
+    use futures::StreamExt;
+    use std::pin::pin;
+
+    fn torrent(state: State, progress: f64, label: &str) -> Torrent {
+        Torrent {
+            completed_time: 0,
+            label: label.to_owned(),
+            name: "example".to_owned(),
+            progress,
+            ratio: 0.0,
+            save_path: "/srv/shared/tests".to_owned(),
+            seeding_time: 0,
+            state,
+            total_remaining: 0,
+            total_size: 0,
+        }
+    }
+
+    #[test]
+    fn diff_snapshots() {
+        // Arrange
+        let previous = HashMap::from([
+            ("a".to_owned(), torrent(State::Downloading, 40.0, "linux")),
+            ("b".to_owned(), torrent(State::Seeding, 100.0, "linux")),
+        ]);
+        let current = HashMap::from([
+            ("a".to_owned(), torrent(State::Seeding, 100.0, "done")),
+            ("c".to_owned(), torrent(State::Queued, 0.0, "")),
+        ]);
+
+        // Act
+        let changes = diff(&previous, &current, &DEFAULT_MILESTONES);
+
+        // Assert
+        let summary: Vec<String> = changes
+            .iter()
+            .map(|change| match change {
+                TorrentChange::Added { id, .. } => format!("added {id}"),
+                TorrentChange::Removed { id, .. } => format!("removed {id}"),
+                TorrentChange::LeftFilter { id, .. } => format!("{id} left filter"),
+                TorrentChange::StateChanged { id, from, to } => {
+                    format!("{id} {from:?} to {to:?}")
+                }
+                TorrentChange::ProgressMilestone { id, milestone } => {
+                    format!("{id} reached {milestone}")
+                }
+                TorrentChange::LabelChanged { id, from, to } => {
+                    format!("{id} label {from} to {to}")
+                }
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "a Downloading to Seeding",
+                "a reached 50",
+                "a reached 75",
+                "a reached 100",
+                "a label linux to done",
+                "removed b",
+                "added c",
+            ]
+        );
+        assert!(changes.first().is_some_and(TorrentChange::is_completion));
+    }
+
+    #[tokio::test]
+    async fn watch_left_filter() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        for id in ["a", "b"] {
+            server
+                .state()
+                .torrents
+                .insert(id.repeat(40), MockTorrent::default());
+        }
+        let mut client = server.client();
+        client.login().await?;
+        let watcher = TorrentWatcher {
+            filters: FilterOptions {
+                state: Some(vec!["Seeding".to_owned()]),
+                ..FilterOptions::default()
+            },
+            interval: Duration::from_millis(10),
+            emit_initial: true,
+            ..TorrentWatcher::default()
+        };
+        let mut changes = pin!(watcher.watch(&mut client));
+
+        // Act
+        let added = [changes.next().await, changes.next().await];
+        {
+            let mut state = server.state();
+            if let Some(torrent) = state.torrents.get_mut(&"a".repeat(40)) {
+                torrent.state = State::Paused;
+            }
+            state.torrents.remove(&"b".repeat(40));
+        }
+        let left = changes.next().await.transpose()?;
+        let removed = changes.next().await.transpose()?;
+
+        // Assert
+        assert!(added
+            .iter()
+            .all(|x| matches!(x, Some(Ok(TorrentChange::Added { .. })))));
+        assert!(matches!(
+            left,
+            Some(TorrentChange::LeftFilter { ref id, ref torrent })
+                if *id == "a".repeat(40) && torrent.state == State::Paused
+        ));
+        assert!(matches!(
+            removed,
+            Some(TorrentChange::Removed { ref id, .. }) if *id == "b".repeat(40)
+        ));
+        Ok(())
+    }
+}
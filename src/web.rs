@@ -0,0 +1,266 @@
+use crate::{RawResponse, Response, Transport, TransportRequest};
+use colored::Colorize;
+use futures::future::BoxFuture;
+use futures::stream::{iter, StreamExt};
+use log::*;
+use reqwest::cookie::Jar;
+use reqwest::multipart::{Form, Part};
+use reqwest::{Client, Request, RequestBuilder};
+use rogue_logging::Error;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::Mutex;
+use tower::limit::RateLimit;
+use tower::{Service, ServiceExt};
+
+/// Future of a request that has been given a permit by the rate limiter
+type Pending = <RateLimit<Client> as Service<Request>>::Future;
+
+/// JSON-RPC over HTTP to the `/json` endpoint of deluge-web
+///
+/// Created by [`DelugeClientFactory::create`](crate::DelugeClientFactory::create)
+/// unless [`TransportKind::Daemon`](crate::TransportKind) is configured.
+pub struct WebTransport {
+    /// URL of the `/json` endpoint
+    pub api_url: String,
+    /// Session cookies set by `auth.login`
+    pub cookies: Arc<Jar>,
+    pub client: RateLimit<Client>,
+    /// Maximum number of requests of a batch in flight at once
+    pub max_concurrent: usize,
+}
+
+impl WebTransport {
+    async fn request(
+        &mut self,
+        method: &str,
+        id: u32,
+        data: Value,
+    ) -> Result<reqwest::Response, Error> {
+        trace!("{} request {method} #{id}", "Sending".bold());
+        let request = self.client.get_ref().post(self.api_url.clone()).json(&data);
+        let pending = self.start(method, request).await?;
+        let start = SystemTime::now();
+        let result = pending.await;
+        let elapsed = start
+            .elapsed()
+            .expect("elapsed should not fail")
+            .as_secs_f64();
+        trace!("{} response #{id} after {elapsed:.3}", "Received".bold());
+        result.map_err(|e| send_error(method, &e))
+    }
+
+    /// Wait for a permit from the rate limiter then start sending the request
+    async fn start(&mut self, method: &str, request: RequestBuilder) -> Result<Pending, Error> {
+        let request = request.build().map_err(|e| send_error(method, &e))?;
+        let start = SystemTime::now();
+        let client = self
+            .client
+            .ready()
+            .await
+            .map_err(|e| send_error(method, &e))?;
+        let duration = start.elapsed().expect("duration should not fail");
+        if duration > Duration::from_millis(200) {
+            trace!(
+                "{} {:.3} for rate limiter",
+                "Waited".bold(),
+                duration.as_secs_f64()
+            );
+        }
+        Ok(client.call(request))
+    }
+}
+
+impl Transport for WebTransport {
+    fn send<'a>(
+        &'a mut self,
+        id: u32,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<Response<Value>, Error>> {
+        Box::pin(async move { self.send_raw(id, method, params).await?.parse(method) })
+    }
+
+    fn send_raw<'a>(
+        &'a mut self,
+        id: u32,
+        method: &'a str,
+        params: Value,
+    ) -> BoxFuture<'a, Result<RawResponse, Error>> {
+        Box::pin(async move {
+            let data = json!({
+                "method": method,
+                "params": params,
+                "id": id
+            });
+            let response = self.request(method, id, data).await?;
+            read_body(method, response).await
+        })
+    }
+
+    /// Send the calls concurrently.
+    ///
+    /// Deluge only accepts one call per request so each call is still sent as a
+    /// separate request. Every request takes a permit from the rate limiter and
+    /// at most [`WebTransport::max_concurrent`] are in flight at once.
+    fn send_batch(
+        &mut self,
+        requests: Vec<TransportRequest>,
+    ) -> BoxFuture<'_, Vec<Result<Response<Value>, Error>>> {
+        Box::pin(async move {
+            let methods: Vec<String> = requests.iter().map(|x| x.method.clone()).collect();
+            let responses = self.send_batch_raw(requests).await;
+            methods
+                .iter()
+                .zip(responses)
+                .map(|(method, response)| response?.parse(method))
+                .collect()
+        })
+    }
+
+    /// Send the calls concurrently as [`WebTransport::send_batch`] does.
+    fn send_batch_raw(
+        &mut self,
+        requests: Vec<TransportRequest>,
+    ) -> BoxFuture<'_, Vec<Result<RawResponse, Error>>> {
+        Box::pin(async move {
+            trace!("{} batch of {} requests", "Sending".bold(), requests.len());
+            let start = SystemTime::now();
+            let max_concurrent = self.max_concurrent.max(1);
+            let transport = Mutex::new(self);
+            let mut responses: Vec<_> = iter(requests.into_iter().enumerate())
+                .map(|(index, request)| send_indexed(&transport, index, request))
+                .buffer_unordered(max_concurrent)
+                .collect()
+                .await;
+            responses.sort_by_key(|(index, _)| *index);
+            let elapsed = start
+                .elapsed()
+                .expect("elapsed should not fail")
+                .as_secs_f64();
+            trace!("{} batch responses after {elapsed:.3}", "Received".bold());
+            responses
+                .into_iter()
+                .map(|(_, response)| response)
+                .collect()
+        })
+    }
+
+    /// Upload a file to the web server's temporary directory
+    fn upload(
+        &mut self,
+        file_name: String,
+        bytes: Vec<u8>,
+    ) -> BoxFuture<'_, Result<String, Error>> {
+        Box::pin(async move {
+            trace!("{} upload {file_name}", "Sending".bold());
+            let upload_url = format!("{}/upload", self.api_url.trim_end_matches("/json"));
+            let part = Part::bytes(bytes).file_name(file_name.clone());
+            let form = Form::new().part("file", part);
+            let request = self.client.get_ref().post(upload_url).multipart(form);
+            let pending = self.start(&format!("upload {file_name}"), request).await?;
+            let start = SystemTime::now();
+            let result = pending.await;
+            let elapsed = start
+                .elapsed()
+                .expect("elapsed should not fail")
+                .as_secs_f64();
+            trace!("{} response after {elapsed:.3}", "Received".bold());
+            let response = result.map_err(|e| Error {
+                action: format!("upload {file_name}"),
+                domain: Some("Deluge API".to_owned()),
+                message: e.to_string(),
+                ..Error::default()
+            })?;
+            let status_code = Some(response.status().as_u16());
+            let upload: Upload = response.json().await.map_err(|e| Error {
+                action: "deserialize response of Deluge upload request".to_owned(),
+                domain: Some("deserialization".to_owned()),
+                message: e.to_string(),
+                status_code,
+                ..Error::default()
+            })?;
+            match upload.files.into_iter().next() {
+                Some(path) if upload.success => Ok(path),
+                _ => Err(Error {
+                    action: format!("upload {file_name}"),
+                    domain: Some("Deluge API".to_owned()),
+                    message: "Upload was not successful".to_owned(),
+                    status_code,
+                    ..Error::default()
+                }),
+            }
+        })
+    }
+}
+
+/// Response of the `/upload` endpoint
+#[derive(Debug, Deserialize)]
+struct Upload {
+    success: bool,
+    #[serde(default)]
+    files: Vec<String>,
+}
+
+/// Send a request of a batch and return it with its index
+///
+/// The lock is only held while waiting for a permit from the rate limiter so
+/// the requests are in flight at the same time.
+async fn send_indexed(
+    transport: &Mutex<&mut WebTransport>,
+    index: usize,
+    request: TransportRequest,
+) -> (usize, Result<RawResponse, Error>) {
+    let data = json!({
+        "method": request.method,
+        "params": request.params,
+        "id": request.id
+    });
+    trace!(
+        "{} request {} #{}",
+        "Sending".bold(),
+        request.method,
+        request.id
+    );
+    let pending = {
+        let mut transport = transport.lock().await;
+        let builder = transport
+            .client
+            .get_ref()
+            .post(transport.api_url.clone())
+            .json(&data);
+        transport.start(&request.method, builder).await
+    };
+    let response = match pending {
+        Ok(pending) => pending.await.map_err(|e| send_error(&request.method, &e)),
+        Err(error) => Err(error),
+    };
+    let response = match response {
+        Ok(response) => read_body(&request.method, response).await,
+        Err(error) => Err(error),
+    };
+    (index, response)
+}
+
+fn send_error(method: &str, error: &reqwest::Error) -> Error {
+    Error {
+        action: format!("send {method} request"),
+        domain: Some("Deluge API".to_owned()),
+        message: error.to_string(),
+        ..Error::default()
+    }
+}
+
+async fn read_body(method: &str, response: reqwest::Response) -> Result<RawResponse, Error> {
+    let status_code = Some(response.status().as_u16());
+    let body = response.text().await.map_err(|e| Error {
+        action: format!("get response body of {method} request"),
+        domain: Some("Deluge API".to_owned()),
+        message: e.to_string(),
+        status_code,
+        ..Error::default()
+    })?;
+    Ok(RawResponse { status_code, body })
+}
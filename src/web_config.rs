@@ -0,0 +1,115 @@
+use crate::{DelugeClient, Response};
+use rogue_logging::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+impl DelugeClient {
+    /// Get the web interface configuration.
+    ///
+    /// The session and password keys are excluded by Deluge.
+    ///
+    /// Only available with the web transport as the daemon has no equivalent.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn get_web_config(&mut self) -> Result<Response<WebConfig>, Error> {
+        self.call("web.get_config", ()).await
+    }
+
+    /// Set the web interface configuration.
+    ///
+    /// Only the fields that are set are sent so a partial config can be applied.
+    ///
+    /// Only available with the web transport as the daemon has no equivalent.
+    ///
+    /// # See Also
+    /// - <https://deluge.readthedocs.io/en/latest/reference/webapi.html>
+    pub async fn set_web_config(&mut self, config: WebConfig) -> Result<Response<()>, Error> {
+        self.call("web.set_config", (config,)).await
+    }
+}
+
+/// Web interface configuration
+///
+/// Keys that are not modelled are kept in `other`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct WebConfig {
+    /// Port the web interface listens on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// Interface the web interface listens on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+    /// Base path when served behind a reverse proxy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// Serve the web interface over HTTPS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub https: Option<bool>,
+    /// Path of the HTTPS private key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pkey: Option<String>,
+    /// Path of the HTTPS certificate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cert: Option<String>,
+    /// Session timeout in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_timeout: Option<u64>,
+    /// Interface theme
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Interface language, empty for the system default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Show the sidebar
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_sidebar: Option<bool>,
+    /// Show filters with no torrents in the sidebar
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sidebar_show_zero: Option<bool>,
+    /// Allow selecting multiple filters in the sidebar
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sidebar_multiple_filters: Option<bool>,
+    /// Show the session speed in the title
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_session_speed: Option<bool>,
+    /// Id of the host to connect to on login
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_daemon: Option<String>,
+    /// Prompt to change the password on first login
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_login: Option<bool>,
+    /// Names of the enabled web plugins
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled_plugins: Option<Vec<String>>,
+
+    /// Configuration values that are not modelled
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::MockServer;
+    use log::trace;
+    use rogue_logging::Error;
+
+    #[tokio::test]
+    async fn get_web_config() -> Result<(), Error> {
+        // Arrange
+        let server = MockServer::start().await?;
+        let mut client = server.client();
+
+        // Act
+        let response = client.login().await?;
+        trace!("{}", response.to_json_pretty());
+        let response = client.get_web_config().await?;
+        trace!("{}", response.to_json_pretty());
+
+        // Assert
+        let result = response.get_result("get_web_config")?;
+        assert!(result.port.is_some());
+        Ok(())
+    }
+}